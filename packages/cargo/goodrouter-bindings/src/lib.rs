@@ -0,0 +1,7 @@
+// the bindings are in the goodrouter crate, this crate only builds them as a shared library
+#[cfg(feature = "ffi")]
+pub use ::goodrouter::ffi::*;
+#[cfg(feature = "python")]
+pub use ::goodrouter::python::*;
+#[cfg(feature = "wasm")]
+pub use ::goodrouter::wasm::*;
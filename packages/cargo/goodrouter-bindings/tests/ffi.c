@@ -0,0 +1,50 @@
+// built and run by the ffi_c test in ffi.rs, against the header and the cdylib
+
+#include <assert.h>
+#include <string.h>
+
+#include "goodrouter.h"
+
+int main(void) {
+  GoodrouterRouter *router = goodrouter_router_new();
+
+  assert(goodrouter_router_insert_route(router, 1, "/product/all") == GOODROUTER_STATUS_OK);
+  assert(goodrouter_router_insert_route(router, 2, "/product/{id}/{tab}") == GOODROUTER_STATUS_OK);
+  assert(goodrouter_router_insert_route(router, 3, "/product/{x}/{y}") ==
+         GOODROUTER_STATUS_AMBIGUOUS_ROUTE);
+
+  GoodrouterMatch *route_match = NULL;
+  assert(goodrouter_router_parse_route(router, "/product/a%20b/c", &route_match) ==
+         GOODROUTER_STATUS_OK);
+  assert(route_match->route_key == 2);
+  assert(route_match->parameter_count == 2);
+  assert(strcmp(route_match->parameters[0].name, "id") == 0);
+  assert(strcmp(route_match->parameters[0].value, "a b") == 0);
+  assert(route_match->parameters[0].value_length == 3);
+  assert(strcmp(route_match->parameters[1].name, "tab") == 0);
+  assert(strcmp(route_match->parameters[1].value, "c") == 0);
+
+  char *path = goodrouter_router_stringify_route(router, route_match->route_key,
+                                                 route_match->parameters,
+                                                 route_match->parameter_count);
+  assert(strcmp(path, "/product/a%20b/c") == 0);
+  goodrouter_string_free(path);
+  goodrouter_match_free(route_match);
+
+  assert(goodrouter_router_parse_route(router, "/product/all", &route_match) ==
+         GOODROUTER_STATUS_OK);
+  assert(route_match->route_key == 1);
+  assert(route_match->parameter_count == 0);
+  goodrouter_match_free(route_match);
+
+  assert(goodrouter_router_parse_route(router, "/not-found", &route_match) ==
+         GOODROUTER_STATUS_NOT_FOUND);
+  assert(route_match == NULL);
+  assert(goodrouter_router_parse_route(router, "/product/%FF/c", &route_match) ==
+         GOODROUTER_STATUS_PARAMETER_VALUE_DECODE);
+  assert(goodrouter_router_stringify_route(router, 2, NULL, 0) == NULL);
+
+  goodrouter_router_free(router);
+
+  return 0;
+}
@@ -0,0 +1,42 @@
+#![cfg(all(feature = "ffi", unix))]
+
+use std::{env, path::PathBuf, process::Command};
+
+// compile the c program in ffi.c against the header and the cdylib, and run it
+#[test]
+fn ffi_c() {
+  let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+  let include_dir = crate_dir.join("../goodrouter/include");
+  let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+  let library_dir = target_dir.join("debug");
+  let program = target_dir.join("ffi-c");
+
+  // cargo does not build a cdylib for the tests, so build it like a user of the library would
+  let status = Command::new(env!("CARGO"))
+    .args(["build", "-p", "goodrouter-bindings", "--features", "ffi"])
+    .arg("--target-dir")
+    .arg(&target_dir)
+    .status()
+    .unwrap();
+  assert!(status.success());
+
+  let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_owned()))
+    .arg(crate_dir.join("tests").join("ffi.c"))
+    .arg("-I")
+    .arg(&include_dir)
+    .arg("-L")
+    .arg(&library_dir)
+    .arg("-lgoodrouter")
+    .arg("-o")
+    .arg(&program)
+    .status()
+    .unwrap();
+  assert!(status.success());
+
+  let status = Command::new(&program)
+    .env("LD_LIBRARY_PATH", &library_dir)
+    .env("DYLD_LIBRARY_PATH", &library_dir)
+    .status()
+    .unwrap();
+  assert!(status.success());
+}
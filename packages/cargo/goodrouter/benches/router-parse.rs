@@ -7,11 +7,19 @@ use std::collections::HashSet;
 pub static TEMPLATE_PLACEHOLDER_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r"\{(.*?)\}").unwrap());
 
+// templates where a parameter is followed by many different anchors, so the parameter node has
+// many children with a parameter
+const WIDE_ANCHORS: [&str; 12] = [
+  ".json", ".xml", "-", "~", "_", "@", "+", "!", ",", ";", "=", ":",
+];
+
 criterion_group!(
   benches,
   router_parse_bench_small,
   router_parse_bench_docker,
-  router_parse_bench_github
+  router_parse_bench_github,
+  router_parse_bench_wide,
+  router_parse_bench_wide_ignore_ascii_case
 );
 criterion_main!(benches);
 
@@ -27,6 +35,26 @@ fn router_parse_bench_github(criterion: &mut Criterion) {
   setup_group(criterion, "github");
 }
 
+fn router_parse_bench_wide(criterion: &mut Criterion) {
+  setup_wide_group(criterion, "wide", false);
+}
+
+fn router_parse_bench_wide_ignore_ascii_case(criterion: &mut Criterion) {
+  setup_wide_group(criterion, "wide ignore ascii case", true);
+}
+
+fn setup_wide_group(criterion: &mut Criterion, name: &str, ignore_ascii_case: bool) {
+  let templates: Vec<_> = WIDE_ANCHORS
+    .iter()
+    .map(|anchor| format!("/file/{{name}}{}{{version}}", anchor))
+    .collect();
+  let templates: Vec<_> = templates.iter().map(|template| template.as_str()).collect();
+
+  bench_templates(criterion, name, &templates, ignore_ascii_case, |index| {
+    format!("somefilenamethatisquitelong{}", index)
+  });
+}
+
 fn setup_group(criterion: &mut Criterion, name: &str) {
   let mut path = std::path::PathBuf::new();
   path.push("..");
@@ -43,6 +71,18 @@ fn setup_group(criterion: &mut Criterion, name: &str) {
     .filter(|line| !line.is_empty())
     .collect();
 
+  bench_templates(criterion, name, &templates, false, |index| {
+    format!("p{}", index)
+  });
+}
+
+fn bench_templates(
+  criterion: &mut Criterion,
+  name: &str,
+  templates: &[&str],
+  ignore_ascii_case: bool,
+  parameter_value: impl Fn(usize) -> String,
+) {
   let mut parameter_names: HashSet<&str> = Default::default();
 
   for template in templates.iter() {
@@ -51,9 +91,7 @@ fn setup_group(criterion: &mut Criterion, name: &str) {
     }
   }
 
-  let parameter_values: Vec<_> = (0..parameter_names.len())
-    .map(|index| format!("p{}", index))
-    .collect();
+  let parameter_values: Vec<_> = (0..parameter_names.len()).map(parameter_value).collect();
 
   let parameters = parameter_names
     .into_iter()
@@ -63,6 +101,7 @@ fn setup_group(criterion: &mut Criterion, name: &str) {
   let template_count = templates.len();
 
   let mut router = Router::new();
+  router.set_ignore_ascii_case(ignore_ascii_case);
 
   for template in templates.iter() {
     router.insert_route(template, template);
@@ -87,4 +126,21 @@ fn setup_group(criterion: &mut Criterion, name: &str) {
   });
 
   group.finish();
+
+  let mut group = criterion.benchmark_group(format!("router insert {}", name));
+
+  group.bench_function(format!("{} routes", template_count), |bencher| {
+    bencher.iter(|| {
+      let mut router = Router::new();
+      router.set_ignore_ascii_case(ignore_ascii_case);
+
+      for template in templates.iter() {
+        router.insert_route(black_box(template), template);
+      }
+
+      router
+    })
+  });
+
+  group.finish();
 }
@@ -0,0 +1,32 @@
+use std::env;
+
+fn main() {
+  println!("cargo:rerun-if-changed=build.rs");
+
+  // only the ffi feature needs the build script
+  if env::var_os("CARGO_FEATURE_FFI").is_some() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+  }
+}
+
+// write the c header of the ffi feature to the out dir, a test checks that the header in the
+// include directory is the same
+#[cfg(feature = "ffi")]
+fn generate_header() {
+  use std::path::PathBuf;
+
+  let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+  let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+  println!("cargo:rerun-if-changed=cbindgen.toml");
+  println!("cargo:rerun-if-changed=src/ffi.rs");
+
+  let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+  cbindgen::Builder::new()
+    .with_config(config)
+    .with_src(crate_dir.join("src").join("ffi.rs"))
+    .generate()
+    .unwrap()
+    .write_to_file(out_dir.join("goodrouter.h"));
+}
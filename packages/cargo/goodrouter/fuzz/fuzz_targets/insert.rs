@@ -0,0 +1,55 @@
+#![no_main]
+
+use goodrouter::router::Router;
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashMap;
+
+// every line is a template, inserted as a route with the template as the key. Every route is
+// stringified and the path is parsed, if it matches a route that route must also be the first of
+// all the routes that match
+fuzz_target!(|data: &str| {
+  let templates: Vec<_> = data.split('\n').collect();
+
+  for ignore_ascii_case in [false, true] {
+    let mut router = Router::new();
+    router.set_ignore_ascii_case(ignore_ascii_case);
+
+    // every other line is an alias of the route of the line before it
+    for (index, template) in templates.iter().cloned().enumerate() {
+      if index % 2 == 0 {
+        let priority = (index % 3) as i32 - 1;
+        let _ = router.try_insert_route_with_priority(template, template, priority);
+      } else {
+        let _ = router.try_insert_alias(template, templates[index - 1]);
+      }
+    }
+
+    router.dump_tree();
+    router.to_dot();
+
+    let routes: Vec<_> = router.routes().collect();
+    for (route_key, _template, parameter_names) in routes {
+      let parameter_values: Vec<_> = (0..parameter_names.len())
+        .map(|index| format!("p{}", index))
+        .collect();
+      let route_parameters: HashMap<_, _> = parameter_names
+        .into_iter()
+        .zip(parameter_values.iter().map(|value| value.as_str()))
+        .collect();
+
+      let path = router
+        .stringify_route(route_key, &route_parameters)
+        .expect("no path for route");
+
+      router.explain_parse(&path);
+      let (parsed_route_key, _route_parameters) = router.parse_route(&path);
+      if parsed_route_key.is_some() {
+        let routes = router.parse_route_all(&path);
+        assert_eq!(
+          routes.first().map(|(route_key, _)| *route_key),
+          parsed_route_key
+        );
+      }
+    }
+  }
+});
@@ -0,0 +1,59 @@
+#![no_main]
+
+use goodrouter::{path_normalization::TrailingSlashPolicy, read_template_lines, router::Router};
+use libfuzzer_sys::fuzz_target;
+use std::sync::OnceLock;
+
+static TEMPLATES: &str = include_str!("../../../../../fixtures/github.txt");
+
+// the github templates, once with the default options and once with every option
+fn routers() -> &'static [Router<'static, &'static str>; 2] {
+  static ROUTERS: OnceLock<[Router<'static, &'static str>; 2]> = OnceLock::new();
+
+  ROUTERS.get_or_init(|| {
+    let mut default_router = Router::new();
+    let mut options_router = Router::new();
+    options_router
+      .set_ignore_ascii_case(true)
+      .set_normalize_unicode(true)
+      .set_collapse_duplicate_slashes(true)
+      .set_remove_dot_segments(true)
+      .set_trailing_slash_policy(TrailingSlashPolicy::Redirect)
+      .set_maximum_parameter_value_length(32)
+      .set_parameter_maximum_value_length("owner", 8);
+
+    for template in read_template_lines(TEMPLATES) {
+      default_router.insert_route(template, template);
+      options_router.insert_route(template, template);
+    }
+
+    [default_router, options_router]
+  })
+}
+
+// every line is a path that is parsed in every way
+fuzz_target!(|data: &str| {
+  for router in routers() {
+    for path in data.split('\n') {
+      let (route_key, route_parameters) = router.parse_route(path);
+      let trace = router.explain_parse(path);
+      let routes = router.parse_route_all(path);
+
+      match router.match_route(path) {
+        Ok(Some(route_match)) => {
+          assert_eq!(route_key, Some(route_match.route_key));
+          assert_eq!(trace.route_key, Some(route_match.route_key));
+          assert_eq!(
+            routes.first(),
+            Some(&(route_match.route_key, route_parameters))
+          );
+
+          router
+            .stringify_route_match(&route_match)
+            .expect("no path for match");
+        }
+        Ok(None) | Err(_) => assert_eq!(route_key, None),
+      }
+    }
+  }
+});
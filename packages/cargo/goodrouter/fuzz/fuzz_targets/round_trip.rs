@@ -0,0 +1,92 @@
+#![no_main]
+
+use goodrouter::router::Router;
+use libfuzzer_sys::fuzz_target;
+use regex::Regex;
+use std::{
+  borrow::Cow,
+  collections::{HashMap, HashSet},
+  sync::OnceLock,
+};
+
+// the first line is a template, the other lines are the values of its parameters. If the route
+// can be stringified, the path must parse to the route with the same parameters. It can always be
+// stringified unless a value cannot be told apart from the anchor that follows it
+fuzz_target!(|data: &str| {
+  let mut lines = data.split('\n');
+  let template = lines.next().unwrap_or_default();
+  let mut values = lines;
+
+  let mut router = Router::new();
+  if router.try_insert_route((), template).is_err() {
+    return;
+  }
+
+  let (_route_key, _template, parameter_names) = router.routes().next().expect("no route");
+
+  // with duplicate names the value of the last parameter wins, so a path may round trip even if a
+  // value cannot be told apart from the anchor after it
+  let unique_parameter_names: HashSet<_> = parameter_names.iter().collect();
+  if unique_parameter_names.len() < parameter_names.len() {
+    return;
+  }
+  let mut route_parameters = HashMap::new();
+  for parameter_name in parameter_names.iter().cloned() {
+    let value = values.next().unwrap_or("x");
+    route_parameters.entry(parameter_name).or_insert(value);
+  }
+
+  let result = router.try_stringify_route((), &route_parameters);
+  assert_eq!(
+    result.is_ok(),
+    representable(template, &route_parameters),
+    "{:?}",
+    result
+  );
+  let Ok(path) = result else {
+    return;
+  };
+
+  let (route_key, parsed_route_parameters) = router.parse_route(&path);
+  assert_eq!(route_key, Some(()), "{}", path);
+
+  let expected_route_parameters: HashMap<_, _> = route_parameters
+    .iter()
+    .map(|(name, value)| (*name, Cow::Borrowed(*value)))
+    .collect();
+  assert_eq!(
+    parsed_route_parameters, expected_route_parameters,
+    "{}",
+    path
+  );
+});
+
+// the value of a parameter ends at the first occurrence of the anchor after it, so the encoded
+// value followed by the anchor may not contain the anchor any earlier. A parameter with an empty
+// anchor takes the rest of the path, so it must be the last, and its value may not be empty
+fn representable(template: &str, route_parameters: &HashMap<&str, &str>) -> bool {
+  // the same as the default parameter placeholder regex of the router
+  static PLACEHOLDER_RE: OnceLock<Regex> = OnceLock::new();
+  let placeholder_re = PLACEHOLDER_RE.get_or_init(|| Regex::new(r"\{(.*?)\}").unwrap());
+  let mut placeholders = placeholder_re.captures_iter(template).peekable();
+
+  while let Some(captures) = placeholders.next() {
+    let placeholder = captures.get(0).unwrap();
+    let anchor_end = placeholders
+      .peek()
+      .map(|captures| captures.get(0).unwrap().start())
+      .unwrap_or(template.len());
+    let anchor = &template[placeholder.end()..anchor_end];
+    let value = urlencoding::encode(route_parameters[&captures[1]]);
+
+    if anchor.is_empty() {
+      if anchor_end < template.len() || value.is_empty() {
+        return false;
+      }
+    } else if format!("{}{}", value, anchor).find(anchor) != Some(value.len()) {
+      return false;
+    }
+  }
+
+  true
+}
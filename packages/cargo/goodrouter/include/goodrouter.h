@@ -0,0 +1,173 @@
+#ifndef GOODROUTER_H
+#define GOODROUTER_H
+
+/* generated from src/ffi.rs by cbindgen, build with the ffi feature to update */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The result of inserting a route or parsing a path.
+ */
+typedef enum GoodrouterStatus {
+  GOODROUTER_STATUS_OK = 0,
+  /**
+   * A pointer is null, or a string is not utf-8.
+   */
+  GOODROUTER_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * The template matches exactly the same paths as the template of another route.
+   */
+  GOODROUTER_STATUS_AMBIGUOUS_ROUTE = 2,
+  /**
+   * No route matches the path.
+   */
+  GOODROUTER_STATUS_NOT_FOUND = 3,
+  /**
+   * The path matches a route, but a parameter value cannot be decoded.
+   */
+  GOODROUTER_STATUS_PARAMETER_VALUE_DECODE = 4,
+  /**
+   * The path would match a route, but a parameter value is too long.
+   */
+  GOODROUTER_STATUS_PARAMETER_VALUE_TOO_LONG = 5,
+  /**
+   * Parsing the path took the maximum number of steps.
+   */
+  GOODROUTER_STATUS_TOO_MANY_STEPS = 6,
+} GoodrouterStatus;
+
+/**
+ * A router with integer route keys. Create it with `goodrouter_router_new` and free it with
+ * `goodrouter_router_free`.
+ */
+typedef struct GoodrouterRouter GoodrouterRouter;
+
+/**
+ * A parameter of a route. The strings are utf-8 with a length, in a match they are also null
+ * terminated.
+ */
+typedef struct GoodrouterParameter {
+  const char *name;
+  size_t name_length;
+  const char *value;
+  size_t value_length;
+} GoodrouterParameter;
+
+/**
+ * The route that matches a path, with the parameters in the order of the template. Free it with
+ * `goodrouter_match_free`.
+ */
+typedef struct GoodrouterMatch {
+  uint64_t route_key;
+  const struct GoodrouterParameter *parameters;
+  size_t parameter_count;
+} GoodrouterMatch;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a router.
+ */
+struct GoodrouterRouter *goodrouter_router_new(void);
+
+/**
+ * Free a router.
+ *
+ * # Safety
+ *
+ * `router` is null or returned by `goodrouter_router_new`, and it is not used after this.
+ */
+void goodrouter_router_free(struct GoodrouterRouter *router);
+
+/**
+ * Insert a route with a null terminated template. The router keeps a copy of the template.
+ *
+ * # Safety
+ *
+ * `router` is null or returned by `goodrouter_router_new`, `template` is null or a null
+ * terminated string.
+ */
+enum GoodrouterStatus goodrouter_router_insert_route(struct GoodrouterRouter *router,
+                                                     uint64_t route_key,
+                                                     const char *template_);
+
+/**
+ * Limit the length of every parameter value, in bytes. A length of 0 removes the limit.
+ *
+ * # Safety
+ *
+ * `router` is null or returned by `goodrouter_router_new`.
+ */
+enum GoodrouterStatus goodrouter_router_set_maximum_parameter_value_length(struct GoodrouterRouter *router,
+                                                                           size_t length);
+
+/**
+ * Limit the number of route nodes that are matched when parsing a path. A number of 0 removes
+ * the limit.
+ *
+ * # Safety
+ *
+ * `router` is null or returned by `goodrouter_router_new`.
+ */
+enum GoodrouterStatus goodrouter_router_set_maximum_parse_steps(struct GoodrouterRouter *router,
+                                                                size_t steps);
+
+/**
+ * Parse a null terminated path. When a route matches, the match is written to `route_match`
+ * and the status is ok, otherwise `route_match` is set to null and the status tells why.
+ *
+ * # Safety
+ *
+ * `router` is null or returned by `goodrouter_router_new`, `path` is null or a null terminated
+ * string, `route_match` is null or points to a pointer that can be written.
+ */
+enum GoodrouterStatus goodrouter_router_parse_route(const struct GoodrouterRouter *router,
+                                                    const char *path,
+                                                    struct GoodrouterMatch **route_match);
+
+/**
+ * Free a match.
+ *
+ * # Safety
+ *
+ * `route_match` is null or returned by `goodrouter_router_parse_route`, and it is not used
+ * after this.
+ */
+void goodrouter_match_free(struct GoodrouterMatch *route_match);
+
+/**
+ * The path of a route with the parameters filled in, as a null terminated string that is freed
+ * with `goodrouter_string_free`. Returns null if there is no route with the key, if a parameter
+ * is missing, or if an argument is invalid.
+ *
+ * # Safety
+ *
+ * `router` is null or returned by `goodrouter_router_new`, `parameters` points to
+ * `parameter_count` parameters, it may be null if there are none.
+ */
+char *goodrouter_router_stringify_route(const struct GoodrouterRouter *router,
+                                        uint64_t route_key,
+                                        const struct GoodrouterParameter *parameters,
+                                        size_t parameter_count);
+
+/**
+ * Free a string returned by `goodrouter_router_stringify_route`.
+ *
+ * # Safety
+ *
+ * `string` is null or returned by `goodrouter_router_stringify_route`, and it is not used after
+ * this.
+ */
+void goodrouter_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GOODROUTER_H */
@@ -0,0 +1,275 @@
+use crate::tower::{MatchedParameters, MatchedRoute};
+use ::axum::{
+  extract::FromRequestParts,
+  response::{IntoResponse, Response},
+};
+use http::{request::Parts, StatusCode};
+use serde::{
+  de::{
+    self,
+    value::{BorrowedStrDeserializer, MapDeserializer},
+    DeserializeOwned, IntoDeserializer, Unexpected, Visitor,
+  },
+  forward_to_deserialize_any, Deserializer,
+};
+use std::{error::Error, fmt};
+
+/// Extracts the parameters of the matched route, deserialized into `T`, a struct or a map. The
+/// values are strings, but they can be deserialized into numbers, booleans and unit enum variants
+/// too. The request has to go through the `Router` or the `GoodrouterLayer` of the `tower`
+/// module first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteParams<T>(pub T);
+
+/// Extracts the key of the matched route. The request has to go through the `Router` or the
+/// `GoodrouterLayer` of the `tower` module first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteKey<K>(pub K);
+
+#[derive(Debug)]
+pub enum RouteRejection {
+  // the request has no match in its extensions, the router or the layer is missing, or the
+  // route key has another type
+  MissingRouteMatch,
+  // the parameters could not be deserialized
+  InvalidRouteParameters { source: de::value::Error },
+}
+
+impl fmt::Display for RouteRejection {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::MissingRouteMatch => write!(f, "no matched route"),
+      Self::InvalidRouteParameters { source } => write!(f, "invalid route parameters: {}", source),
+    }
+  }
+}
+
+impl Error for RouteRejection {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::MissingRouteMatch => None,
+      Self::InvalidRouteParameters { source } => Some(source),
+    }
+  }
+}
+
+impl IntoResponse for RouteRejection {
+  fn into_response(self) -> Response {
+    let status = match self {
+      Self::MissingRouteMatch => StatusCode::INTERNAL_SERVER_ERROR,
+      Self::InvalidRouteParameters { .. } => StatusCode::BAD_REQUEST,
+    };
+
+    (status, self.to_string()).into_response()
+  }
+}
+
+impl<T, S> FromRequestParts<S> for RouteParams<T>
+where
+  T: DeserializeOwned,
+  S: Send + Sync,
+{
+  type Rejection = RouteRejection;
+
+  async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    let MatchedParameters(route_parameters) = parts
+      .extensions
+      .get::<MatchedParameters>()
+      .ok_or(RouteRejection::MissingRouteMatch)?;
+
+    let deserializer = MapDeserializer::new(route_parameters.iter().map(
+      |(parameter_name, parameter_value)| {
+        (*parameter_name, ParameterValueDeserializer(parameter_value))
+      },
+    ));
+    let value = T::deserialize(deserializer)
+      .map_err(|source| RouteRejection::InvalidRouteParameters { source })?;
+
+    Ok(Self(value))
+  }
+}
+
+impl<K, S> FromRequestParts<S> for RouteKey<K>
+where
+  K: Copy + Send + Sync + 'static,
+  S: Send + Sync,
+{
+  type Rejection = RouteRejection;
+
+  async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    let route_match = parts
+      .extensions
+      .get::<MatchedRoute<K>>()
+      .ok_or(RouteRejection::MissingRouteMatch)?;
+
+    Ok(Self(route_match.route_key))
+  }
+}
+
+// deserializes a parameter value, parses the value if a number, a boolean or a char is expected
+struct ParameterValueDeserializer<'de>(&'de str);
+
+macro_rules! deserialize_parsed {
+  ($($method:ident => $visit:ident,)*) => {
+    $(
+      fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.parse() {
+          Ok(value) => visitor.$visit(value),
+          Err(_) => Err(de::Error::invalid_value(Unexpected::Str(self.0), &visitor)),
+        }
+      }
+    )*
+  };
+}
+
+impl<'de> Deserializer<'de> for ParameterValueDeserializer<'de> {
+  type Error = de::value::Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_borrowed_str(self.0)
+  }
+
+  deserialize_parsed! {
+    deserialize_bool => visit_bool,
+    deserialize_i8 => visit_i8,
+    deserialize_i16 => visit_i16,
+    deserialize_i32 => visit_i32,
+    deserialize_i64 => visit_i64,
+    deserialize_u8 => visit_u8,
+    deserialize_u16 => visit_u16,
+    deserialize_u32 => visit_u32,
+    deserialize_u64 => visit_u64,
+    deserialize_f32 => visit_f32,
+    deserialize_f64 => visit_f64,
+    deserialize_char => visit_char,
+  }
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_some(self)
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    BorrowedStrDeserializer::new(self.0).deserialize_enum(name, variants, visitor)
+  }
+
+  forward_to_deserialize_any! {
+    i128 u128 str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+    identifier ignored_any
+  }
+}
+
+impl<'de> IntoDeserializer<'de, de::value::Error> for ParameterValueDeserializer<'de> {
+  type Deserializer = Self;
+
+  fn into_deserializer(self) -> Self::Deserializer {
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{router::Router as RouteTable, tower::Router};
+  use ::axum::{
+    body::{self, Body},
+    handler::Handler,
+  };
+  use ::tower::ServiceExt;
+  use http::{Method, Request};
+  use serde::Deserialize;
+  use std::collections::HashMap;
+
+  #[derive(Debug, Deserialize)]
+  #[serde(rename_all = "kebab-case")]
+  enum Tab {
+    Info,
+    Reviews,
+  }
+
+  #[derive(Debug, Deserialize)]
+  struct ProductTab {
+    id: u64,
+    tab: Tab,
+  }
+
+  async fn product_tab(
+    RouteKey(route_key): RouteKey<&'static str>,
+    RouteParams(product_tab): RouteParams<ProductTab>,
+  ) -> String {
+    format!("{} {} {:?}", route_key, product_tab.id, product_tab.tab)
+  }
+
+  async fn product_detail(
+    RouteParams(route_parameters): RouteParams<HashMap<String, String>>,
+  ) -> String {
+    route_parameters["id"].clone()
+  }
+
+  // the route key is not a `&str`
+  async fn product_key(RouteKey(route_key): RouteKey<u32>) -> String {
+    route_key.to_string()
+  }
+
+  fn router() -> Router<&'static str, Body, Body, std::convert::Infallible> {
+    let mut route_table = RouteTable::new();
+    route_table
+      .insert_route("product-detail", "/product/{id}")
+      .insert_route("product-key", "/product/{id}/key")
+      .insert_route("product-tab", "/product/{id}/{tab}");
+
+    let mut router = Router::new(route_table);
+    router
+      .route_method("product-detail", Method::GET, product_detail.with_state(()))
+      .route_method("product-key", Method::GET, product_key.with_state(()))
+      .route_method("product-tab", Method::GET, product_tab.with_state(()));
+    router
+  }
+
+  async fn get(path: &str) -> (StatusCode, String) {
+    let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+    let response = router().oneshot(request).await.unwrap();
+
+    let status = response.status();
+    let body = body::to_bytes(response.into_body(), usize::MAX)
+      .await
+      .unwrap();
+
+    (status, String::from_utf8(body.to_vec()).unwrap())
+  }
+
+  #[tokio::test]
+  async fn axum_extractors() {
+    assert_eq!(
+      get("/product/1/reviews").await,
+      (StatusCode::OK, "product-tab 1 Reviews".to_owned())
+    );
+
+    assert_eq!(
+      get("/product/a%20b").await,
+      (StatusCode::OK, "a b".to_owned())
+    );
+
+    let (status, _body) = get("/product/x/info").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _body) = get("/product/1/settings").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = get("/product/1/key").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body, "no matched route");
+  }
+}
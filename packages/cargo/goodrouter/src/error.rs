@@ -0,0 +1,159 @@
+use std::{borrow::Cow, error::Error, fmt};
+
+pub type ParameterValueDecodeError = Box<dyn Error + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertRouteError<K> {
+  // the template matches exactly the same paths as the template of the route with `route_key`
+  AmbiguousRoute { route_key: K },
+  // paths are normalized to unicode normalization form C, but the template is not
+  TemplateNotNormalized,
+  // an alias was inserted for a route that does not exist
+  UnknownRoute,
+  // the template of an alias has a different number of parameters than the route
+  AliasParameterCount,
+}
+
+impl<K> fmt::Display for InsertRouteError<K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::AmbiguousRoute { .. } => write!(f, "ambiguous route"),
+      Self::TemplateNotNormalized => write!(f, "template not in unicode normalization form C"),
+      Self::UnknownRoute => write!(f, "alias for unknown route"),
+      Self::AliasParameterCount => write!(f, "alias parameters do not match route parameters"),
+    }
+  }
+}
+
+impl<K: fmt::Debug> Error for InsertRouteError<K> {}
+
+#[derive(Debug)]
+pub enum ParseRouteError<'r, 'f, K> {
+  // the path matched a route, but one of the parameter values could not be decoded
+  ParameterValueDecode {
+    route_key: K,
+    parameter_name: &'r str,
+    parameter_value: Cow<'f, str>,
+    source: ParameterValueDecodeError,
+  },
+  // the path would have matched a route, but one of the parameter values is longer than the
+  // maximum length for that parameter
+  ParameterValueTooLong {
+    route_key: K,
+    parameter_name: &'r str,
+    parameter_value: Cow<'f, str>,
+    maximum_length: usize,
+  },
+  // parsing the path took the maximum number of steps before it matched a route
+  TooManySteps {
+    maximum_steps: usize,
+  },
+}
+
+impl<'r, 'f, K> ParseRouteError<'r, 'f, K> {
+  pub fn into_owned(self) -> ParseRouteError<'r, 'static, K> {
+    match self {
+      Self::ParameterValueDecode {
+        route_key,
+        parameter_name,
+        parameter_value,
+        source,
+      } => ParseRouteError::ParameterValueDecode {
+        route_key,
+        parameter_name,
+        parameter_value: Cow::Owned(parameter_value.into_owned()),
+        source,
+      },
+      Self::ParameterValueTooLong {
+        route_key,
+        parameter_name,
+        parameter_value,
+        maximum_length,
+      } => ParseRouteError::ParameterValueTooLong {
+        route_key,
+        parameter_name,
+        parameter_value: Cow::Owned(parameter_value.into_owned()),
+        maximum_length,
+      },
+      Self::TooManySteps { maximum_steps } => ParseRouteError::TooManySteps { maximum_steps },
+    }
+  }
+}
+
+impl<'r, 'f, K> fmt::Display for ParseRouteError<'r, 'f, K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::ParameterValueDecode {
+        parameter_name,
+        parameter_value,
+        source,
+        ..
+      } => write!(
+        f,
+        "could not decode value {:?} for parameter {}: {}",
+        parameter_value, parameter_name, source
+      ),
+      Self::ParameterValueTooLong {
+        parameter_name,
+        parameter_value,
+        maximum_length,
+        ..
+      } => write!(
+        f,
+        "value of parameter {} is {} bytes, longer than the maximum of {}",
+        parameter_name,
+        parameter_value.len(),
+        maximum_length
+      ),
+      Self::TooManySteps { maximum_steps } => write!(
+        f,
+        "parsing the path took the maximum of {} steps",
+        maximum_steps
+      ),
+    }
+  }
+}
+
+impl<'r, 'f, K: fmt::Debug> Error for ParseRouteError<'r, 'f, K> {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::ParameterValueDecode { source, .. } => Some(source.as_ref()),
+      Self::ParameterValueTooLong { .. } | Self::TooManySteps { .. } => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StringifyRouteError<'r, K> {
+  // there is no route with the key
+  UnknownRoute,
+  // there is no value for a parameter of the route
+  MissingParameter {
+    parameter_name: &'r str,
+  },
+  // the path of the route does not parse to the same route with the same parameters. This happens
+  // when a value contains the anchor that follows it, when a value is empty or too long, or when
+  // the path matches another route first. `parsed_route_key` is the route the path parsed to
+  NotRepresentable {
+    path: String,
+    parsed_route_key: Option<K>,
+  },
+}
+
+impl<'r, K> fmt::Display for StringifyRouteError<'r, K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::UnknownRoute => write!(f, "unknown route"),
+      Self::MissingParameter { parameter_name } => {
+        write!(f, "missing value for parameter {}", parameter_name)
+      }
+      Self::NotRepresentable { path, .. } => write!(
+        f,
+        "path {:?} does not parse to the route with the same parameters",
+        path
+      ),
+    }
+  }
+}
+
+impl<'r, K: fmt::Debug> Error for StringifyRouteError<'r, K> {}
@@ -0,0 +1,331 @@
+use crate::{
+  error::{InsertRouteError, ParseRouteError},
+  owned_router::OwnedRouter,
+};
+use std::{
+  collections::HashMap,
+  ffi::{c_char, CStr, CString},
+  ptr, slice, str,
+};
+
+/// A router with integer route keys. Create it with `goodrouter_router_new` and free it with
+/// `goodrouter_router_free`.
+pub struct GoodrouterRouter {
+  router: OwnedRouter<u64>,
+}
+
+/// The result of inserting a route or parsing a path.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoodrouterStatus {
+  Ok = 0,
+  /// A pointer is null, or a string is not utf-8.
+  InvalidArgument = 1,
+  /// The template matches exactly the same paths as the template of another route.
+  AmbiguousRoute = 2,
+  /// No route matches the path.
+  NotFound = 3,
+  /// The path matches a route, but a parameter value cannot be decoded.
+  ParameterValueDecode = 4,
+  /// The path would match a route, but a parameter value is too long.
+  ParameterValueTooLong = 5,
+  /// Parsing the path took the maximum number of steps.
+  TooManySteps = 6,
+}
+
+/// A parameter of a route. The strings are utf-8 with a length, in a match they are also null
+/// terminated.
+#[repr(C)]
+pub struct GoodrouterParameter {
+  pub name: *const c_char,
+  pub name_length: usize,
+  pub value: *const c_char,
+  pub value_length: usize,
+}
+
+/// The route that matches a path, with the parameters in the order of the template. Free it with
+/// `goodrouter_match_free`.
+#[repr(C)]
+pub struct GoodrouterMatch {
+  pub route_key: u64,
+  pub parameters: *const GoodrouterParameter,
+  pub parameter_count: usize,
+}
+
+/// Create a router.
+#[no_mangle]
+pub extern "C" fn goodrouter_router_new() -> *mut GoodrouterRouter {
+  Box::into_raw(Box::new(GoodrouterRouter {
+    router: OwnedRouter::new(),
+  }))
+}
+
+/// Free a router.
+///
+/// # Safety
+///
+/// `router` is null or returned by `goodrouter_router_new`, and it is not used after this.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_router_free(router: *mut GoodrouterRouter) {
+  if !router.is_null() {
+    drop(Box::from_raw(router));
+  }
+}
+
+/// Insert a route with a null terminated template. The router keeps a copy of the template.
+///
+/// # Safety
+///
+/// `router` is null or returned by `goodrouter_router_new`, `template` is null or a null
+/// terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_router_insert_route(
+  router: *mut GoodrouterRouter,
+  route_key: u64,
+  template: *const c_char,
+) -> GoodrouterStatus {
+  let Some(router) = router.as_mut() else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+  let Some(template) = c_str(template) else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+
+  match router
+    .router
+    .try_insert_route(route_key, template.to_owned())
+  {
+    Ok(()) => GoodrouterStatus::Ok,
+    Err(InsertRouteError::AmbiguousRoute { .. }) => GoodrouterStatus::AmbiguousRoute,
+    // these do not happen, the router does not normalize unicode and there are no aliases
+    Err(
+      InsertRouteError::TemplateNotNormalized
+      | InsertRouteError::UnknownRoute
+      | InsertRouteError::AliasParameterCount,
+    ) => GoodrouterStatus::InvalidArgument,
+  }
+}
+
+/// Limit the length of every parameter value, in bytes. A length of 0 removes the limit.
+///
+/// # Safety
+///
+/// `router` is null or returned by `goodrouter_router_new`.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_router_set_maximum_parameter_value_length(
+  router: *mut GoodrouterRouter,
+  length: usize,
+) -> GoodrouterStatus {
+  let Some(router) = router.as_mut() else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+
+  router
+    .router
+    .set_maximum_parameter_value_length(Some(length).filter(|length| *length > 0));
+
+  GoodrouterStatus::Ok
+}
+
+/// Limit the number of route nodes that are matched when parsing a path. A number of 0 removes
+/// the limit.
+///
+/// # Safety
+///
+/// `router` is null or returned by `goodrouter_router_new`.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_router_set_maximum_parse_steps(
+  router: *mut GoodrouterRouter,
+  steps: usize,
+) -> GoodrouterStatus {
+  let Some(router) = router.as_mut() else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+
+  router
+    .router
+    .set_maximum_parse_steps(Some(steps).filter(|steps| *steps > 0));
+
+  GoodrouterStatus::Ok
+}
+
+/// Parse a null terminated path. When a route matches, the match is written to `route_match`
+/// and the status is ok, otherwise `route_match` is set to null and the status tells why.
+///
+/// # Safety
+///
+/// `router` is null or returned by `goodrouter_router_new`, `path` is null or a null terminated
+/// string, `route_match` is null or points to a pointer that can be written.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_router_parse_route(
+  router: *const GoodrouterRouter,
+  path: *const c_char,
+  route_match: *mut *mut GoodrouterMatch,
+) -> GoodrouterStatus {
+  let Some(route_match) = route_match.as_mut() else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+  *route_match = ptr::null_mut();
+
+  let Some(router) = router.as_ref() else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+  let Some(path) = c_str(path) else {
+    return GoodrouterStatus::InvalidArgument;
+  };
+
+  let router = router.router.router();
+  let (route_key, route_parameters) = match router.try_parse_route(path) {
+    Ok((Some(route_key), route_parameters)) => (route_key, route_parameters),
+    Ok((None, _)) => return GoodrouterStatus::NotFound,
+    Err(ParseRouteError::ParameterValueDecode { .. }) => {
+      return GoodrouterStatus::ParameterValueDecode
+    }
+    Err(ParseRouteError::ParameterValueTooLong { .. }) => {
+      return GoodrouterStatus::ParameterValueTooLong
+    }
+    Err(ParseRouteError::TooManySteps { .. }) => return GoodrouterStatus::TooManySteps,
+  };
+
+  let parameters: Box<[_]> = router
+    .parameter_names_of(route_key)
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|parameter_name| {
+      let parameter_value = route_parameters.get(parameter_name)?;
+      let (name, name_length) = into_c_chars(parameter_name);
+      let (value, value_length) = into_c_chars(parameter_value);
+      Some(GoodrouterParameter {
+        name,
+        name_length,
+        value,
+        value_length,
+      })
+    })
+    .collect();
+  let parameter_count = parameters.len();
+
+  *route_match = Box::into_raw(Box::new(GoodrouterMatch {
+    route_key,
+    parameters: Box::into_raw(parameters) as *const GoodrouterParameter,
+    parameter_count,
+  }));
+
+  GoodrouterStatus::Ok
+}
+
+/// Free a match.
+///
+/// # Safety
+///
+/// `route_match` is null or returned by `goodrouter_router_parse_route`, and it is not used
+/// after this.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_match_free(route_match: *mut GoodrouterMatch) {
+  if route_match.is_null() {
+    return;
+  }
+
+  let route_match = Box::from_raw(route_match);
+  let parameters = Box::from_raw(ptr::slice_from_raw_parts_mut(
+    route_match.parameters as *mut GoodrouterParameter,
+    route_match.parameter_count,
+  ));
+  for parameter in parameters.iter() {
+    free_c_chars(parameter.name, parameter.name_length);
+    free_c_chars(parameter.value, parameter.value_length);
+  }
+}
+
+/// The path of a route with the parameters filled in, as a null terminated string that is freed
+/// with `goodrouter_string_free`. Returns null if there is no route with the key, if a parameter
+/// is missing, or if an argument is invalid.
+///
+/// # Safety
+///
+/// `router` is null or returned by `goodrouter_router_new`, `parameters` points to
+/// `parameter_count` parameters, it may be null if there are none.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_router_stringify_route(
+  router: *const GoodrouterRouter,
+  route_key: u64,
+  parameters: *const GoodrouterParameter,
+  parameter_count: usize,
+) -> *mut c_char {
+  let Some(router) = router.as_ref() else {
+    return ptr::null_mut();
+  };
+  let parameters = match (parameters.is_null(), parameter_count) {
+    (true, 0) => &[],
+    (true, _) => return ptr::null_mut(),
+    (false, _) => slice::from_raw_parts(parameters, parameter_count),
+  };
+
+  let mut route_parameters = HashMap::new();
+  for parameter in parameters {
+    let (Some(name), Some(value)) = (
+      str_from_parts(parameter.name, parameter.name_length),
+      str_from_parts(parameter.value, parameter.value_length),
+    ) else {
+      return ptr::null_mut();
+    };
+    route_parameters.insert(name, value);
+  }
+
+  router
+    .router
+    .router()
+    .stringify_route(route_key, &route_parameters)
+    .and_then(|path| CString::new(path.into_owned()).ok())
+    .map(CString::into_raw)
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Free a string returned by `goodrouter_router_stringify_route`.
+///
+/// # Safety
+///
+/// `string` is null or returned by `goodrouter_router_stringify_route`, and it is not used after
+/// this.
+#[no_mangle]
+pub unsafe extern "C" fn goodrouter_string_free(string: *mut c_char) {
+  if !string.is_null() {
+    drop(CString::from_raw(string));
+  }
+}
+
+unsafe fn c_str<'a>(string: *const c_char) -> Option<&'a str> {
+  if string.is_null() {
+    return None;
+  }
+
+  CStr::from_ptr(string).to_str().ok()
+}
+
+unsafe fn str_from_parts<'a>(string: *const c_char, length: usize) -> Option<&'a str> {
+  if string.is_null() {
+    return None;
+  }
+
+  str::from_utf8(slice::from_raw_parts(string as *const u8, length)).ok()
+}
+
+// copy a string to the heap, with a null after it, the string may contain nulls so the length is
+// returned too
+fn into_c_chars(string: &str) -> (*const c_char, usize) {
+  let mut chars = Vec::with_capacity(string.len() + 1);
+  chars.extend_from_slice(string.as_bytes());
+  chars.push(0);
+
+  (
+    Box::into_raw(chars.into_boxed_slice()) as *const c_char,
+    string.len(),
+  )
+}
+
+unsafe fn free_c_chars(chars: *const c_char, length: usize) {
+  drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+    chars as *mut u8,
+    length + 1,
+  )));
+}
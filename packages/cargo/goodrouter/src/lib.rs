@@ -1,4 +1,21 @@
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(test, feature = "wasm", feature = "ffi", feature = "python"))]
+mod owned_router;
+pub mod parse_trace;
+pub mod path_normalization;
+#[cfg(feature = "python")]
+pub mod python;
 mod route_node;
 pub mod router;
 mod string_utility;
 mod template;
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use template::read_template_lines;
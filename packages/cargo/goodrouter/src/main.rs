@@ -0,0 +1,429 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use goodrouter::{
+  error::InsertRouteError, path_normalization::TrailingSlashPolicy, read_template_lines,
+  router::Router,
+};
+use std::{
+  collections::HashMap,
+  error::Error,
+  fs,
+  io::{self, Write},
+  path::PathBuf,
+  process::ExitCode,
+  time::{Duration, Instant},
+};
+
+#[derive(Parser)]
+#[command(name = "goodrouter", version, about = "Test route templates and paths")]
+struct Cli {
+  /// File with one route template per line, the template is also the key of the route
+  #[arg(short, long)]
+  templates: PathBuf,
+
+  #[arg(long)]
+  ignore_ascii_case: bool,
+
+  #[arg(long)]
+  normalize_unicode: bool,
+
+  #[arg(long)]
+  collapse_duplicate_slashes: bool,
+
+  #[arg(long)]
+  remove_dot_segments: bool,
+
+  #[arg(long, value_enum, default_value_t = TrailingSlash::Strict)]
+  trailing_slash: TrailingSlash,
+
+  #[arg(long)]
+  maximum_parameter_value_length: Option<usize>,
+
+  #[arg(long)]
+  maximum_parse_steps: Option<usize>,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Parse a path and print the key and parameters of the route
+  Parse {
+    path: String,
+
+    /// Print a trace of the matching of the path
+    #[arg(long)]
+    explain: bool,
+  },
+  /// Stringify a route, parameters are passed as name=value
+  Stringify {
+    template: String,
+    parameters: Vec<String>,
+  },
+  /// Print the tree of route nodes
+  Tree {
+    /// Print the tree as a graphviz digraph
+    #[arg(long)]
+    dot: bool,
+  },
+  /// Report templates that are ambiguous or that are shadowed by other templates
+  Check,
+  /// Time parsing a generated path for every template
+  Bench {
+    #[arg(long, default_value_t = 100_000)]
+    iterations: usize,
+  },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TrailingSlash {
+  Strict,
+  Ignore,
+  Redirect,
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+
+  let templates = match fs::read_to_string(&cli.templates) {
+    Ok(templates) => templates,
+    Err(error) => {
+      eprintln!("could not read {}: {}", cli.templates.display(), error);
+      return ExitCode::from(2);
+    }
+  };
+
+  match run(&cli, &templates, &mut io::stdout().lock()) {
+    Ok(true) => ExitCode::SUCCESS,
+    Ok(false) => ExitCode::FAILURE,
+    Err(error) => {
+      eprintln!("{}", error);
+      ExitCode::from(2)
+    }
+  }
+}
+
+// run the command, returns false if the command did not succeed, for instance if the path did
+// not match a route
+fn run(cli: &Cli, templates: &str, output: &mut dyn Write) -> Result<bool, Box<dyn Error>> {
+  let templates = read_template_lines(templates);
+
+  let mut router = create_router(cli);
+
+  let mut inserted = true;
+  for template in templates.iter().cloned() {
+    match router.try_insert_route(template, template) {
+      Ok(_) => {}
+      // check reports the templates that cannot be inserted, the other commands fail
+      Err(error) if matches!(cli.command, Command::Check) => {
+        inserted = false;
+        write_insert_error(template, error, output)?;
+      }
+      Err(error) => return Err(format!("{}: {}", template, error).into()),
+    }
+  }
+
+  match &cli.command {
+    Command::Parse { path, explain } => {
+      if *explain {
+        write!(output, "{}", router.explain_parse(path))?;
+      }
+
+      if let Some(route_match) = router
+        .match_route(path)
+        .map_err(|error| error.to_string())?
+      {
+        writeln!(output, "{}", route_match.route_key)?;
+        for &parameter_name in router
+          .parameter_names_of(route_match.route_key)
+          .unwrap_or_default()
+        {
+          writeln!(
+            output,
+            "{}={}",
+            parameter_name, route_match.route_parameters[parameter_name]
+          )?;
+        }
+        if route_match.redirect {
+          writeln!(output, "redirect to {}", route_match.canonical_path)?;
+        }
+        Ok(true)
+      } else {
+        writeln!(output, "no route")?;
+        Ok(false)
+      }
+    }
+    Command::Stringify {
+      template,
+      parameters,
+    } => {
+      let route_key = templates
+        .iter()
+        .cloned()
+        .find(|route_key| route_key == template)
+        .ok_or_else(|| format!("unknown template {}", template))?;
+
+      let mut route_parameters = HashMap::new();
+      for parameter in parameters {
+        let (name, value) = parameter
+          .split_once('=')
+          .ok_or_else(|| format!("expected name=value, got {}", parameter))?;
+        route_parameters.insert(name, value);
+      }
+
+      for parameter_name in router.parameter_names_of(route_key).unwrap_or_default() {
+        if !route_parameters.contains_key(parameter_name) {
+          return Err(format!("missing parameter {}", parameter_name).into());
+        }
+      }
+
+      let path = router
+        .stringify_route(route_key, &route_parameters)
+        .ok_or_else(|| format!("cannot stringify {}", template))?;
+      writeln!(output, "{}", path)?;
+      Ok(true)
+    }
+    Command::Tree { dot } => {
+      if *dot {
+        write!(output, "{}", router.to_dot())?;
+      } else {
+        write!(output, "{}", router.dump_tree())?;
+      }
+      Ok(true)
+    }
+    Command::Check => check(&router, inserted, output),
+    Command::Bench { iterations } => {
+      let paths = sample_paths(&router);
+      if paths.is_empty() {
+        return Err("no templates".into());
+      }
+
+      let start = Instant::now();
+      for iteration in 0..*iterations {
+        router.parse_route(&paths[iteration % paths.len()]);
+      }
+      let elapsed = start.elapsed();
+
+      writeln!(
+        output,
+        "{} parses of {} paths in {:?}, {:?} per parse",
+        iterations,
+        paths.len(),
+        elapsed,
+        Duration::from_secs_f64(elapsed.as_secs_f64() / (*iterations).max(1) as f64)
+      )?;
+      Ok(true)
+    }
+  }
+}
+
+fn write_insert_error(
+  template: &str,
+  error: InsertRouteError<&str>,
+  output: &mut dyn Write,
+) -> io::Result<()> {
+  match error {
+    InsertRouteError::AmbiguousRoute { route_key } if route_key == template => {
+      writeln!(output, "duplicate: {}", template)
+    }
+    InsertRouteError::AmbiguousRoute { route_key } => {
+      writeln!(output, "ambiguous: {} and {}", template, route_key)
+    }
+    error => writeln!(output, "error: {}: {}", template, error),
+  }
+}
+
+fn check<'r>(
+  router: &Router<'r, &'r str>,
+  inserted: bool,
+  output: &mut dyn Write,
+) -> Result<bool, Box<dyn Error>> {
+  let mut ok = inserted;
+
+  // a route is shadowed if a path for that route matches another route
+  let route_keys: Vec<_> = router.routes().map(|(route_key, ..)| route_key).collect();
+  for (route_key, path) in route_keys.into_iter().zip(sample_paths(router)) {
+    let (parsed_route_key, _route_parameters) = router.parse_route(&path);
+    if parsed_route_key != Some(route_key) {
+      ok = false;
+      match parsed_route_key {
+        Some(parsed_route_key) => writeln!(
+          output,
+          "shadowed: {} by {} for {}",
+          route_key, parsed_route_key, path
+        )?,
+        None => writeln!(output, "unreachable: {} for {}", route_key, path)?,
+      }
+    }
+  }
+
+  if ok {
+    writeln!(output, "ok")?;
+  }
+
+  Ok(ok)
+}
+
+fn create_router<'r>(cli: &Cli) -> Router<'r, &'r str> {
+  let mut router = Router::new();
+
+  router
+    .set_ignore_ascii_case(cli.ignore_ascii_case)
+    .set_normalize_unicode(cli.normalize_unicode)
+    .set_collapse_duplicate_slashes(cli.collapse_duplicate_slashes)
+    .set_remove_dot_segments(cli.remove_dot_segments)
+    .set_maximum_parameter_value_length(cli.maximum_parameter_value_length)
+    .set_maximum_parse_steps(cli.maximum_parse_steps)
+    .set_trailing_slash_policy(match cli.trailing_slash {
+      TrailingSlash::Strict => TrailingSlashPolicy::Strict,
+      TrailingSlash::Ignore => TrailingSlashPolicy::Ignore,
+      TrailingSlash::Redirect => TrailingSlashPolicy::Redirect,
+    });
+
+  router
+}
+
+// a path for every route, in the order of `routes`, with parameter values p0, p1, ...
+fn sample_paths<'r>(router: &Router<'r, &'r str>) -> Vec<String> {
+  router
+    .routes()
+    .map(|(route_key, _template, parameter_names)| {
+      let parameter_values: Vec<_> = (0..parameter_names.len())
+        .map(|index| format!("p{}", index))
+        .collect();
+      let route_parameters: HashMap<_, _> = parameter_names
+        .into_iter()
+        .zip(parameter_values.iter().map(|value| value.as_str()))
+        .collect();
+      router
+        .stringify_route(route_key, &route_parameters)
+        .unwrap()
+        .into_owned()
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TEMPLATES: &str = "
+    /about
+    /product/all
+    /product/{id}
+    /product/{id}/{tab}
+  ";
+
+  fn run_command(arguments: &[&str], templates: &str) -> (bool, String) {
+    let cli = Cli::parse_from(
+      ["goodrouter", "--templates", "templates.txt"]
+        .iter()
+        .chain(arguments),
+    );
+    let mut output = Vec::new();
+    let ok = run(&cli, templates, &mut output).unwrap();
+    (ok, String::from_utf8(output).unwrap())
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!(
+      run_command(&["parse", "/product/1/specs"], TEMPLATES),
+      (true, "/product/{id}/{tab}\nid=1\ntab=specs\n".to_owned())
+    );
+    assert_eq!(
+      run_command(&["parse", "/not-found"], TEMPLATES),
+      (false, "no route\n".to_owned())
+    );
+    assert_eq!(
+      run_command(
+        &["--trailing-slash", "redirect", "parse", "/about/"],
+        TEMPLATES
+      ),
+      (true, "/about\nredirect to /about\n".to_owned())
+    );
+
+    let cli = Cli::parse_from([
+      "goodrouter",
+      "--templates",
+      "templates.txt",
+      "--maximum-parse-steps",
+      "1",
+      "parse",
+      "/product/1",
+    ]);
+    let error = run(&cli, TEMPLATES, &mut Vec::new()).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "parsing the path took the maximum of 1 steps"
+    );
+
+    let (ok, output) = run_command(&["parse", "--explain", "/product/all"], TEMPLATES);
+    assert!(ok);
+    assert!(output.starts_with("parse \"/product/all\"\n"));
+    assert!(output.ends_with("/product/all\n"));
+  }
+
+  #[test]
+  fn stringify() {
+    assert_eq!(
+      run_command(
+        &["stringify", "/product/{id}/{tab}", "id=1", "tab=a/b"],
+        TEMPLATES
+      ),
+      (true, "/product/1/a%2Fb\n".to_owned())
+    );
+  }
+
+  #[test]
+  fn tree() {
+    let (ok, output) = run_command(&["tree"], TEMPLATES);
+    assert!(ok);
+    assert!(output.contains("=> \"/product/all\"\n"));
+
+    let (ok, output) = run_command(&["tree", "--dot"], TEMPLATES);
+    assert!(ok);
+    assert!(output.starts_with("digraph {\n"));
+  }
+
+  #[test]
+  fn check() {
+    assert_eq!(
+      run_command(&["check"], TEMPLATES),
+      (true, "ok\n".to_owned())
+    );
+
+    assert_eq!(
+      run_command(
+        &["check"],
+        "
+          /a/{x}
+          /a/{y}
+          /a/{x}
+        "
+      ),
+      (
+        false,
+        "ambiguous: /a/{y} and /a/{x}
+duplicate: /a/{x}
+"
+        .to_owned()
+      )
+    );
+
+    assert_eq!(
+      run_command(
+        &["--maximum-parameter-value-length", "1", "check"],
+        "/a/{x}/b"
+      ),
+      (false, "unreachable: /a/{x}/b for /a/p0/b\n".to_owned())
+    );
+  }
+
+  #[test]
+  fn bench() {
+    let (ok, output) = run_command(&["bench", "--iterations", "10"], TEMPLATES);
+    assert!(ok);
+    assert!(output.starts_with("10 parses of 4 paths in "));
+  }
+}
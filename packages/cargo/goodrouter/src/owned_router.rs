@@ -0,0 +1,117 @@
+use crate::{error::InsertRouteError, router::Router};
+use std::{hash::Hash, mem::ManuallyDrop};
+
+// a router that owns its templates, for the bindings to other languages. These cannot hold on to
+// a `Router`, because it borrows the templates
+pub struct OwnedRouter<K> {
+  // the router borrows the templates, it is dropped before them
+  router: ManuallyDrop<Router<'static, K>>,
+  // the templates, from `Box::into_raw`. These are raw pointers and not boxes, a box may not be
+  // aliased while the router holds references to it
+  templates: Vec<*mut str>,
+}
+
+// SAFETY: the templates are only read through the router, and freed when the router is dropped,
+// so the owned router is as thread safe as a router that borrows strings
+unsafe impl<K: Send> Send for OwnedRouter<K> {}
+unsafe impl<K: Sync> Sync for OwnedRouter<K> {}
+
+impl<K: Eq + Hash + Copy> OwnedRouter<K> {
+  pub fn new() -> Self {
+    Self {
+      router: ManuallyDrop::new(Router::new()),
+      templates: Vec::new(),
+    }
+  }
+
+  pub fn router(&self) -> &Router<'_, K> {
+    &self.router
+  }
+
+  // the router is not handed out mutably, that would let its templates outlive this
+  #[cfg(feature = "ffi")]
+  pub fn set_maximum_parameter_value_length(&mut self, value: Option<usize>) {
+    self.router.set_maximum_parameter_value_length(value);
+  }
+
+  #[cfg(feature = "ffi")]
+  pub fn set_maximum_parse_steps(&mut self, value: Option<usize>) {
+    self.router.set_maximum_parse_steps(value);
+  }
+
+  pub fn try_insert_route(
+    &mut self,
+    route_key: K,
+    template: String,
+  ) -> Result<(), InsertRouteError<K>> {
+    let template = self.keep_template(template);
+    self.router.try_insert_route(route_key, template)?;
+
+    Ok(())
+  }
+
+  // keep the template as long as the router, also when inserting fails, because the router may
+  // already refer to it
+  fn keep_template(&mut self, template: String) -> &'static str {
+    let template = Box::into_raw(template.into_boxed_str());
+    self.templates.push(template);
+
+    // SAFETY: the template is never changed, and freed only after the router is dropped, so it
+    // lives as long as the router. The static lifetime is never handed out
+    unsafe { &*template }
+  }
+}
+
+impl<K> Drop for OwnedRouter<K> {
+  fn drop(&mut self) {
+    // SAFETY: the router is not used after this, and the templates are freed only once, when
+    // nothing refers to them anymore
+    unsafe {
+      ManuallyDrop::drop(&mut self.router);
+      for template in self.templates.drain(..) {
+        drop(Box::from_raw(template));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // also run this with `cargo +nightly miri test --lib owned_router`
+  #[test]
+  fn owned_router() {
+    let mut router = OwnedRouter::new();
+
+    for (route_key, template) in ["/product/all", "/product/{id}"].into_iter().enumerate() {
+      router
+        .try_insert_route(route_key, template.to_owned())
+        .unwrap();
+    }
+    assert!(router
+      .try_insert_route(2, "/product/{x}".to_owned())
+      .is_err());
+
+    let path = String::from("/product/1");
+    let (route_key, route_parameters) = router.router().parse_route(&path);
+    assert_eq!(route_key, Some(1));
+    assert_eq!(route_parameters["id"], "1");
+
+    let (route_key, _route_parameters) = router.router().parse_route("/product/all");
+    assert_eq!(route_key, Some(0));
+
+    // the templates are still read after more were added
+    router
+      .try_insert_route(3, "/category/{name}".to_owned())
+      .unwrap();
+    assert_eq!(router.router().template_of(0), Some("/product/all"));
+    assert_eq!(
+      router
+        .router()
+        .stringify_route(1, &[("id", "2")].into_iter().collect())
+        .as_deref(),
+      Some("/product/2")
+    );
+  }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTraceEvent<'r, K> {
+  // start matching a (normalized) path from the root node
+  Start {
+    path: String,
+  },
+  // visit a node, `offset` is the position in the path where matching the node starts
+  Visit {
+    depth: usize,
+    anchor: &'r str,
+    has_parameter: bool,
+    offset: usize,
+  },
+  // the path starts with the anchor of the node
+  AnchorMatched {
+    depth: usize,
+  },
+  // the path does not start with the anchor of the node
+  AnchorMismatched {
+    depth: usize,
+  },
+  // the anchor was found after a parameter value of `value_length` bytes
+  AnchorFound {
+    depth: usize,
+    value_length: usize,
+  },
+  // the anchor was not found in the first `searched_length` bytes of the path. If `limited` is
+  // true, the search was cut short by the maximum parameter value length
+  AnchorNotFound {
+    depth: usize,
+    searched_length: usize,
+    limited: bool,
+  },
+  // the parameter value is longer than the maximum parameter value length, or than the maximum
+  // length of the route parameter
+  ParameterValueTooLong {
+    depth: usize,
+    value_length: usize,
+    maximum_length: usize,
+  },
+  // there is no path left for the parameter value
+  EmptyParameterValue {
+    depth: usize,
+  },
+  // the child at `child_index`, in order of priority, matched the rest of the path
+  ChildMatched {
+    depth: usize,
+    child_index: usize,
+  },
+  // the node ends a route and the path is matched completely
+  RouteMatched {
+    depth: usize,
+    route_key: K,
+  },
+  // none of the children matched and the node does not end a route that matches
+  NoMatch {
+    depth: usize,
+  },
+  // the parse stopped before matching a node, because it took the maximum number of steps
+  TooManySteps {
+    depth: usize,
+    maximum_steps: usize,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTrace<'r, K> {
+  pub events: Vec<ParseTraceEvent<'r, K>>,
+  pub route_key: Option<K>,
+  path_length: usize,
+}
+
+impl<'r, K> ParseTrace<'r, K> {
+  pub(crate) fn start(&mut self, path: &str) {
+    self.path_length = path.len();
+    self.events.push(ParseTraceEvent::Start {
+      path: path.to_owned(),
+    });
+  }
+
+  pub(crate) fn visit(&mut self, depth: usize, anchor: &'r str, has_parameter: bool, path: &str) {
+    let offset = self.path_length - path.len();
+    self.events.push(ParseTraceEvent::Visit {
+      depth,
+      anchor,
+      has_parameter,
+      offset,
+    });
+  }
+
+  pub(crate) fn push(&mut self, event: ParseTraceEvent<'r, K>) {
+    self.events.push(event);
+  }
+}
+
+impl<'r, K> Default for ParseTrace<'r, K> {
+  fn default() -> Self {
+    Self {
+      events: Default::default(),
+      route_key: None,
+      path_length: 0,
+    }
+  }
+}
+
+impl<'r, K: fmt::Debug> fmt::Display for ParseTrace<'r, K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for event in &self.events {
+      match event {
+        ParseTraceEvent::Start { path } => writeln!(f, "parse {:?}", path)?,
+        ParseTraceEvent::Visit {
+          depth,
+          anchor,
+          has_parameter,
+          offset,
+        } => writeln!(
+          f,
+          "{:indent$}visit {}{:?} at {}",
+          "",
+          if *has_parameter { "{} " } else { "" },
+          anchor,
+          offset,
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::AnchorMatched { depth } => {
+          writeln!(f, "{:indent$}  anchor matched", "", indent = depth * 2)?
+        }
+        ParseTraceEvent::AnchorMismatched { depth } => {
+          writeln!(f, "{:indent$}  anchor mismatched", "", indent = depth * 2)?
+        }
+        ParseTraceEvent::AnchorFound {
+          depth,
+          value_length,
+        } => writeln!(
+          f,
+          "{:indent$}  anchor found after {} bytes",
+          "",
+          value_length,
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::AnchorNotFound {
+          depth,
+          searched_length,
+          limited,
+        } => writeln!(
+          f,
+          "{:indent$}  anchor not found in {} bytes{}",
+          "",
+          searched_length,
+          if *limited {
+            ", limited by the maximum parameter value length"
+          } else {
+            ""
+          },
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::ParameterValueTooLong {
+          depth,
+          value_length,
+          maximum_length,
+        } => writeln!(
+          f,
+          "{:indent$}  parameter value of {} bytes is longer than the maximum of {}",
+          "",
+          value_length,
+          maximum_length,
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::EmptyParameterValue { depth } => writeln!(
+          f,
+          "{:indent$}  empty parameter value",
+          "",
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::ChildMatched { depth, child_index } => writeln!(
+          f,
+          "{:indent$}  child {} matched",
+          "",
+          child_index,
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::RouteMatched { depth, route_key } => writeln!(
+          f,
+          "{:indent$}  route {:?} matched",
+          "",
+          route_key,
+          indent = depth * 2
+        )?,
+        ParseTraceEvent::NoMatch { depth } => {
+          writeln!(f, "{:indent$}  no match", "", indent = depth * 2)?
+        }
+        ParseTraceEvent::TooManySteps {
+          depth,
+          maximum_steps,
+        } => writeln!(
+          f,
+          "{:indent$}stopped after the maximum of {} steps",
+          "",
+          maximum_steps,
+          indent = depth * 2
+        )?,
+      }
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,126 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingSlashPolicy {
+  // the trailing slash of the path must match the template
+  #[default]
+  Strict,
+  // if the path does not match, try again with the trailing slash added or removed
+  Ignore,
+  // like ignore, but flag the match as a redirect if the canonical path is different from the
+  // path that was requested
+  Redirect,
+}
+
+// replace every sequence of slashes with a single one, returns None if nothing was changed
+pub fn collapse_duplicate_slashes(path: &str) -> Option<String> {
+  if !path.contains("//") {
+    return None;
+  }
+
+  let mut result = String::with_capacity(path.len());
+  let mut previous_char = None;
+  for current_char in path.chars() {
+    if current_char == '/' && previous_char == Some('/') {
+      continue;
+    }
+    result.push(current_char);
+    previous_char = Some(current_char);
+  }
+
+  Some(result)
+}
+
+// remove `.` and `..` segments as described in section 5.2.4 of RFC 3986, returns None if
+// nothing was changed
+pub fn remove_dot_segments(path: &str) -> Option<String> {
+  if !path
+    .split('/')
+    .any(|segment| segment == "." || segment == "..")
+  {
+    return None;
+  }
+
+  let mut input = path;
+  let mut output = String::with_capacity(path.len());
+
+  while !input.is_empty() {
+    if let Some(rest) = input
+      .strip_prefix("../")
+      .or_else(|| input.strip_prefix("./"))
+    {
+      input = rest;
+    } else if input.starts_with("/./") {
+      input = &input[2..];
+    } else if input == "/." {
+      input = "/";
+    } else if input.starts_with("/../") || input == "/.." {
+      input = if input == "/.." { "/" } else { &input[3..] };
+      let index = output.rfind('/').unwrap_or(0);
+      output.truncate(index);
+    } else if input == "." || input == ".." {
+      input = "";
+    } else {
+      let start = if input.starts_with('/') { 1 } else { 0 };
+      let index = input[start..]
+        .find('/')
+        .map(|index| index + start)
+        .unwrap_or(input.len());
+      output.push_str(&input[..index]);
+      input = &input[index..];
+    }
+  }
+
+  Some(output)
+}
+
+// add a trailing slash if there is none, otherwise remove it. Returns None for paths that have
+// no segment to add a trailing slash to
+pub fn toggle_trailing_slash(path: &str) -> Option<String> {
+  if path.is_empty() || path == "/" {
+    None
+  } else if let Some(path) = path.strip_suffix('/') {
+    Some(path.to_owned())
+  } else {
+    Some(format!("{}/", path))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn collapse_duplicate_slashes_test() {
+    assert_eq!(collapse_duplicate_slashes("/a/b/"), None);
+    assert_eq!(
+      collapse_duplicate_slashes("//a///b//"),
+      Some("/a/b/".to_owned())
+    );
+  }
+
+  #[test]
+  fn remove_dot_segments_test() {
+    assert_eq!(remove_dot_segments("/a/b.c/.d"), None);
+    assert_eq!(
+      remove_dot_segments("/a/b/c/./../../g"),
+      Some("/a/g".to_owned())
+    );
+    assert_eq!(
+      remove_dot_segments("mid/content=5/../6"),
+      Some("mid/6".to_owned())
+    );
+    assert_eq!(
+      remove_dot_segments("/product/./1/"),
+      Some("/product/1/".to_owned())
+    );
+    assert_eq!(remove_dot_segments("/a/.."), Some("/".to_owned()));
+    assert_eq!(remove_dot_segments("/../a"), Some("/a".to_owned()));
+    assert_eq!(remove_dot_segments("/a/."), Some("/a/".to_owned()));
+  }
+
+  #[test]
+  fn toggle_trailing_slash_test() {
+    assert_eq!(toggle_trailing_slash("/"), None);
+    assert_eq!(toggle_trailing_slash("/a"), Some("/a/".to_owned()));
+    assert_eq!(toggle_trailing_slash("/a/"), Some("/a".to_owned()));
+  }
+}
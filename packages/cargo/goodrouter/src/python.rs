@@ -0,0 +1,124 @@
+use crate::{owned_router::OwnedRouter, template::read_template_lines};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use std::{collections::HashMap, fs};
+
+/// The router for python. Route keys can be any hashable object, the parameters are a dict.
+#[pyclass(name = "Router", module = "goodrouter")]
+pub struct PyRouter {
+  router: OwnedRouter<usize>,
+  // the keys of the routes, the route key in the router is the index in this vec
+  route_keys: Vec<Py<PyAny>>,
+  // the index of every key in `route_keys`
+  route_indices: Py<PyDict>,
+}
+
+#[pymethods]
+impl PyRouter {
+  #[new]
+  pub fn new(py: Python<'_>) -> Self {
+    Self {
+      router: OwnedRouter::new(),
+      route_keys: Vec::new(),
+      route_indices: PyDict::new(py).unbind(),
+    }
+  }
+
+  /// Create a router from a file with a template per line, like the fixtures. The templates are
+  /// also the route keys.
+  #[staticmethod]
+  pub fn load_templates(py: Python<'_>, path: &str) -> PyResult<Self> {
+    let templates = fs::read_to_string(path)?;
+
+    let mut router = Self::new(py);
+    for template in read_template_lines(&templates) {
+      router.insert_route(
+        py,
+        template.into_pyobject(py)?.into_any(),
+        template.to_owned(),
+      )?;
+    }
+
+    Ok(router)
+  }
+
+  /// Insert a route, raises a `ValueError` if the route cannot be inserted.
+  pub fn insert_route(
+    &mut self,
+    py: Python<'_>,
+    route_key: Bound<'_, PyAny>,
+    template: String,
+  ) -> PyResult<()> {
+    // a new key is only registered when the route is inserted
+    let route_indices = self.route_indices.bind(py);
+    let route_index = match route_indices.get_item(&route_key)? {
+      Some(route_index) => route_index.extract()?,
+      None => self.route_keys.len(),
+    };
+
+    self
+      .router
+      .try_insert_route(route_index, template)
+      .map_err(|error| PyValueError::new_err(error.to_string()))?;
+
+    if route_index == self.route_keys.len() {
+      route_indices.set_item(&route_key, route_index)?;
+      self.route_keys.push(route_key.unbind());
+    }
+
+    Ok(())
+  }
+
+  /// Parse a path, returns the route key, or `None`, and a dict with the parameters.
+  pub fn parse_route<'py>(
+    &self,
+    py: Python<'py>,
+    path: &str,
+  ) -> PyResult<(Option<Py<PyAny>>, Bound<'py, PyDict>)> {
+    let (route_index, route_parameters) = self.router.router().parse_route(path);
+
+    let parameters = PyDict::new(py);
+    for (parameter_name, parameter_value) in route_parameters {
+      parameters.set_item(parameter_name, parameter_value.as_ref())?;
+    }
+
+    Ok((
+      route_index.map(|route_index| self.route_keys[route_index].clone_ref(py)),
+      parameters,
+    ))
+  }
+
+  /// The path of a route with the parameters filled in. Returns `None` if there is no route with
+  /// the key or if a parameter is missing.
+  #[pyo3(signature = (route_key, route_parameters = None))]
+  pub fn stringify_route(
+    &self,
+    py: Python<'_>,
+    route_key: Bound<'_, PyAny>,
+    route_parameters: Option<HashMap<String, String>>,
+  ) -> PyResult<Option<String>> {
+    let Some(route_index) = self.route_indices.bind(py).get_item(&route_key)? else {
+      return Ok(None);
+    };
+    let route_index = route_index.extract()?;
+
+    let route_parameters = route_parameters.unwrap_or_default();
+    let route_parameters = route_parameters
+      .iter()
+      .map(|(name, value)| (name.as_str(), value.as_str()))
+      .collect();
+
+    Ok(
+      self
+        .router
+        .router()
+        .stringify_route(route_index, &route_parameters)
+        .map(|path| path.into_owned()),
+    )
+  }
+}
+
+/// The python module, build it with maturin.
+#[pymodule]
+pub fn goodrouter(module: &Bound<'_, PyModule>) -> PyResult<()> {
+  module.add_class::<PyRouter>()
+}
@@ -1,36 +1,90 @@
+pub mod route_node_arena;
+pub mod route_node_dump;
 pub mod route_node_merge;
-pub mod route_node_rc;
 pub mod route_node_utility;
 
+use aho_corasick::AhoCorasick;
+use memchr::memmem::Finder;
 use route_node_utility::*;
-use std::{
-  cell::RefCell,
-  cmp::Ordering,
-  collections::BTreeSet,
-  rc::{Rc, Weak},
-};
+use std::{cmp::Ordering, fmt};
 
-pub type RouteNodeRc<'r, K> = Rc<RefCell<RouteNode<'r, K>>>;
-pub type RouteNodeWeak<'r, K> = Weak<RefCell<RouteNode<'r, K>>>;
+// route nodes are stored in a vector, the arena, and refer to each other by their index in the
+// arena. The root node is always the first node
+pub const ROOT_NODE_INDEX: usize = 0;
 
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct RouteNode<'r, K> {
   // the route's key, if any
   pub route_key: Option<K>,
   // the route parameter names
   pub route_parameter_names: Vec<&'r str>,
+  // the template of the route
+  pub route_template: &'r str,
+  // is this route an alias of the route with the same key
+  pub route_alias: bool,
+  // the priority of the route, routes with a higher priority are matched first
+  pub route_priority: i32,
+  // the maximum length of the value of every route parameter, if any
+  pub route_parameter_maximum_value_lengths: Vec<Option<usize>>,
+  // the highest priority of the routes of this node and its descendants, nodes are ordered by
+  // this priority first
+  priority: i32,
   // suffix that comes after the parameter value (if any!) of the path
   anchor: &'r str,
+  // precomputed searcher for the anchor, so finding the anchor takes linear time
+  anchor_finder: Finder<'r>,
   // does this node has a parameter
   has_parameter: bool,
-  // children that represent the rest of the path that needs to be matched
-  children: BTreeSet<RouteNodeRc<'r, K>>,
-  // parent node, should only be null for the root node
-  parent: Option<RouteNodeWeak<'r, K>>,
+  // indices of the children that represent the rest of the path that needs to be matched, in
+  // the order of the nodes
+  children: Vec<usize>,
+  // the first byte of the anchor of every child without a parameter and with a non empty
+  // anchor, in ascii lowercase, and the position of that child in the children. Sorted, so the
+  // children that may match a path are found with a binary search on the first byte of the path
+  literal_children: Vec<(u8, usize)>,
+  // positions of the children that are not in the literal children, because they have a
+  // parameter or an empty anchor. These children are always tried
+  other_children: Vec<usize>,
+  // searcher for the anchors of the children that have a parameter, so the path is scanned once
+  // for all of them. Only there when ignoring ascii case and two or more of these children have
+  // an anchor, otherwise searching with the anchor finder of every child is faster. The patterns
+  // are in the order of the children
+  parameter_anchors_searcher: Option<AhoCorasick>,
+  // index of the parent node, should only be none for the root node
+  parent: Option<usize>,
+}
+
+// the parent, the anchor finder, the parameter anchors searcher and the positions of the children
+// are left out
+impl<'r, K: fmt::Debug> fmt::Debug for RouteNode<'r, K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("RouteNode")
+      .field("route_key", &self.route_key)
+      .field("route_parameter_names", &self.route_parameter_names)
+      .field("route_template", &self.route_template)
+      .field("route_alias", &self.route_alias)
+      .field("route_priority", &self.route_priority)
+      .field(
+        "route_parameter_maximum_value_lengths",
+        &self.route_parameter_maximum_value_lengths,
+      )
+      .field("priority", &self.priority)
+      .field("anchor", &self.anchor)
+      .field("has_parameter", &self.has_parameter)
+      .field("children", &self.children)
+      .finish()
+  }
 }
 
 impl<'r, K> Ord for RouteNode<'r, K> {
   fn cmp(&self, other: &Self) -> Ordering {
+    if self.priority > other.priority {
+      return Ordering::Less;
+    }
+    if self.priority < other.priority {
+      return Ordering::Greater;
+    }
+
     if self.anchor.len() < other.anchor.len() {
       return Ordering::Greater;
     }
@@ -66,7 +120,9 @@ impl<'r, K> Eq for RouteNode<'r, K> {}
 
 impl<'r, K> PartialEq for RouteNode<'r, K> {
   fn eq(&self, other: &Self) -> bool {
-    self.anchor == other.anchor && self.has_parameter == other.has_parameter
+    self.anchor == other.anchor
+      && self.has_parameter == other.has_parameter
+      && self.priority == other.priority
   }
 }
 
@@ -75,9 +131,18 @@ impl<'r, K> Default for RouteNode<'r, K> {
     Self {
       route_key: None,
       route_parameter_names: Default::default(),
+      route_template: Default::default(),
+      route_alias: Default::default(),
+      route_priority: Default::default(),
+      route_parameter_maximum_value_lengths: Default::default(),
+      priority: Default::default(),
       anchor: Default::default(),
+      anchor_finder: Finder::new(""),
       has_parameter: Default::default(),
       children: Default::default(),
+      literal_children: Default::default(),
+      other_children: Default::default(),
+      parameter_anchors_searcher: Default::default(),
       parent: Default::default(),
     }
   }
@@ -91,7 +156,14 @@ mod tests {
 
   #[test]
   fn route_ordering() {
-    let nodes = vec![
+    let nodes = [
+      RouteNode {
+        route_key: None,
+        has_parameter: true,
+        anchor: "",
+        priority: 1,
+        ..Default::default()
+      },
       RouteNode {
         route_key: None,
         has_parameter: false,
@@ -116,6 +188,13 @@ mod tests {
         anchor: "x",
         ..Default::default()
       },
+      RouteNode {
+        route_key: None,
+        has_parameter: false,
+        anchor: "yyy",
+        priority: -1,
+        ..Default::default()
+      },
     ];
 
     let nodes_expected = nodes.iter();
@@ -0,0 +1,785 @@
+use super::route_node_merge::*;
+use super::*;
+use crate::error::InsertRouteError;
+use crate::parse_trace::{ParseTrace, ParseTraceEvent};
+use crate::string_utility::{floor_char_boundary, str_find, str_starts_with};
+use crate::template::template_pairs::parse_template_pairs;
+use aho_corasick::Input;
+use regex::Regex;
+use smallvec::SmallVec;
+use std::borrow::Cow;
+
+// a node on the stack of a parse, with what is needed to try its next child
+struct ParseFrame<'n, 'f> {
+  node_index: usize,
+  // the rest of the path, after the anchor and the parameter value of the node
+  path: &'f str,
+  // the number of parameter values before the parameter value of the node
+  parameter_values_length: usize,
+  // the children that are not tried yet
+  candidate_children: CandidateChildren<'n>,
+  // the position of the child that is tried
+  child_index: usize,
+  // the anchors of the children with a parameter are searched for all at once, when the first
+  // of these children is visited
+  parameter_anchor_indices: Option<Vec<Option<usize>>>,
+  parameter_anchor_count: usize,
+}
+
+// the stack of a parse, the frames of most paths fit in the inline part of the stack so a parse
+// does not allocate
+type ParseStack<'n, 'f> = SmallVec<[ParseFrame<'n, 'f>; 16]>;
+
+impl<'n, 'f> ParseFrame<'n, 'f> {
+  fn new<'r, K>(
+    node_index: usize,
+    node: &'n RouteNode<'r, K>,
+    path: &'f str,
+    parameter_values_length: usize,
+  ) -> Self {
+    Self {
+      node_index,
+      path,
+      parameter_values_length,
+      candidate_children: route_node_candidate_children(node, path),
+      child_index: 0,
+      parameter_anchor_indices: None,
+      parameter_anchor_count: 0,
+    }
+  }
+}
+
+// counts the steps of parsing a path, a step is matching a node against the path. The steps
+// of every parse for a path are counted together
+pub struct ParseSteps {
+  pub maximum: Option<usize>,
+  pub count: usize,
+  // true if a parse stopped because the maximum number of steps was reached
+  pub exhausted: bool,
+}
+
+impl ParseSteps {
+  pub fn new(maximum: Option<usize>) -> Self {
+    Self {
+      maximum,
+      count: 0,
+      exhausted: false,
+    }
+  }
+
+  // take a step, returns false if the maximum number of steps was reached
+  fn take<K>(&mut self, depth: usize, trace: Option<&mut ParseTrace<'_, K>>) -> bool {
+    if let Some(maximum_steps) = self.maximum {
+      if self.count >= maximum_steps {
+        self.exhausted = true;
+        if let Some(trace) = trace {
+          trace.push(ParseTraceEvent::TooManySteps {
+            depth,
+            maximum_steps,
+          });
+        }
+        return false;
+      }
+    }
+
+    self.count += 1;
+    true
+  }
+}
+
+// match the path against the node and its descendants. The parameter values of the route are
+// pushed on `parameter_values`, when there is no match `parameter_values` is left as it was. If
+// `route_parameter_limits` is false, the maximum lengths of route parameters are not checked.
+// The nodes that are being matched are kept on a stack instead of recursing, so a deep tree
+// cannot overflow the call stack. Children are tried depth first, in order, like a recursive
+// parse would.
+// A node is only matched when its parent matched, and it only matches in one way, because a
+// parameter value always ends at the first occurrence of the anchor. So every node is matched
+// at most once, and a parse takes at most as many steps as there are nodes. When there are no
+// steps left the parse stops without a match, and `steps.exhausted` is set
+#[allow(clippy::too_many_arguments)]
+pub fn route_node_parse<'r, 'f, K: Copy>(
+  nodes: &[RouteNode<'r, K>],
+  node_index: usize,
+  path: &'f str,
+  maximum_parameter_value_length: Option<usize>,
+  route_parameter_limits: bool,
+  ignore_ascii_case: bool,
+  steps: &mut ParseSteps,
+  parameter_values: &mut Vec<&'f str>,
+  mut trace: Option<&mut ParseTrace<'r, K>>,
+) -> Option<usize> {
+  if !steps.take(0, trace.as_deref_mut()) {
+    return None;
+  }
+
+  let parameter_values_length = parameter_values.len();
+  let node = &nodes[node_index];
+  let (parameter_value, path) = route_node_match(
+    node,
+    path,
+    None,
+    maximum_parameter_value_length,
+    ignore_ascii_case,
+    0,
+    trace.as_deref_mut(),
+  )?;
+
+  // a node is pushed on the stack after it matched
+  let mut stack: ParseStack = SmallVec::new();
+  stack.push(ParseFrame::new(
+    node_index,
+    node,
+    path,
+    parameter_values.len(),
+  ));
+  parameter_values.extend(parameter_value);
+
+  loop {
+    let depth = stack.len().checked_sub(1)?;
+    let frame = &mut stack[depth];
+    let node_index = frame.node_index;
+    let node = &nodes[node_index];
+
+    // only the children that may match the rest of the path are tried, until one matches
+    let mut child_match = None;
+    for child_index in frame.candidate_children.by_ref() {
+      let child_node_index = node.children[child_index];
+      let child_node = &nodes[child_node_index];
+
+      let child_anchor_index = match &node.parameter_anchors_searcher {
+        Some(parameter_anchors_searcher)
+          if child_node.has_parameter && !child_node.anchor.is_empty() =>
+        {
+          let path = frame.path;
+          let parameter_anchor_indices = frame.parameter_anchor_indices.get_or_insert_with(|| {
+            route_node_find_parameter_anchors(
+              parameter_anchors_searcher,
+              path,
+              maximum_parameter_value_length,
+            )
+          });
+          frame.parameter_anchor_count += 1;
+          Some(parameter_anchor_indices[frame.parameter_anchor_count - 1])
+        }
+        _ => None,
+      };
+
+      if !steps.take(depth + 1, trace.as_deref_mut()) {
+        parameter_values.truncate(parameter_values_length);
+        return None;
+      }
+
+      if let Some((parameter_value, path)) = route_node_match(
+        child_node,
+        frame.path,
+        child_anchor_index,
+        maximum_parameter_value_length,
+        ignore_ascii_case,
+        depth + 1,
+        trace.as_deref_mut(),
+      ) {
+        frame.child_index = child_index;
+        child_match = Some((child_node_index, parameter_value, path));
+        break;
+      }
+    }
+
+    if let Some((child_node_index, parameter_value, path)) = child_match {
+      stack.push(ParseFrame::new(
+        child_node_index,
+        &nodes[child_node_index],
+        path,
+        parameter_values.len(),
+      ));
+      parameter_values.extend(parameter_value);
+      continue;
+    }
+
+    // if the node had a route name and there is no path left to match against then we found a route
+    if frame.path.is_empty() {
+      if let Some(route_key) = node.route_key {
+        let too_long_parameter_value = if route_parameter_limits {
+          route_node_find_too_long_parameter_value(node, parameter_values)
+        } else {
+          None
+        };
+
+        if let Some((value_length, maximum_length)) = too_long_parameter_value {
+          if let Some(trace) = trace.as_deref_mut() {
+            trace.push(ParseTraceEvent::ParameterValueTooLong {
+              depth,
+              value_length,
+              maximum_length,
+            });
+          }
+        } else {
+          if let Some(trace) = trace.as_deref_mut() {
+            trace.push(ParseTraceEvent::RouteMatched { depth, route_key });
+
+            // every ancestor matched the child that is above it on the stack
+            for (depth, frame) in stack.iter().enumerate().rev().skip(1) {
+              trace.push(ParseTraceEvent::ChildMatched {
+                depth,
+                child_index: frame.child_index,
+              });
+            }
+          }
+          return Some(node_index);
+        }
+      }
+    }
+
+    if let Some(trace) = trace.as_deref_mut() {
+      trace.push(ParseTraceEvent::NoMatch { depth });
+    }
+
+    parameter_values.truncate(frame.parameter_values_length);
+    stack.pop();
+  }
+}
+
+// like `route_node_parse`, but instead of stopping at the first match, every leaf node that
+// matches the path is appended to `matches`, in the order `route_node_parse` would try them.
+// When there are no steps left, the matches that were found so far are kept
+#[allow(clippy::too_many_arguments)]
+pub fn route_node_parse_all<'r, 'f, K: Copy>(
+  nodes: &[RouteNode<'r, K>],
+  node_index: usize,
+  path: &'f str,
+  maximum_parameter_value_length: Option<usize>,
+  ignore_ascii_case: bool,
+  steps: &mut ParseSteps,
+  parameter_values: &mut Vec<&'f str>,
+  matches: &mut Vec<(usize, Vec<&'f str>)>,
+) {
+  if !steps.take::<K>(0, None) {
+    return;
+  }
+
+  let parameter_values_length = parameter_values.len();
+  let node = &nodes[node_index];
+  let Some((parameter_value, path)) = route_node_match(
+    node,
+    path,
+    None,
+    maximum_parameter_value_length,
+    ignore_ascii_case,
+    0,
+    None,
+  ) else {
+    return;
+  };
+
+  let mut stack: ParseStack = SmallVec::new();
+  stack.push(ParseFrame::new(
+    node_index,
+    node,
+    path,
+    parameter_values.len(),
+  ));
+  parameter_values.extend(parameter_value);
+
+  while let Some(frame) = stack.last_mut() {
+    let node = &nodes[frame.node_index];
+
+    let mut child_match = None;
+    for child_index in frame.candidate_children.by_ref() {
+      let child_node_index = node.children[child_index];
+
+      if !steps.take::<K>(0, None) {
+        parameter_values.truncate(parameter_values_length);
+        return;
+      }
+
+      if let Some((parameter_value, path)) = route_node_match(
+        &nodes[child_node_index],
+        frame.path,
+        None,
+        maximum_parameter_value_length,
+        ignore_ascii_case,
+        0,
+        None,
+      ) {
+        child_match = Some((child_node_index, parameter_value, path));
+        break;
+      }
+    }
+
+    if let Some((child_node_index, parameter_value, path)) = child_match {
+      stack.push(ParseFrame::new(
+        child_node_index,
+        &nodes[child_node_index],
+        path,
+        parameter_values.len(),
+      ));
+      parameter_values.extend(parameter_value);
+      continue;
+    }
+
+    // the descendants of the node are tried before the node itself
+    if frame.path.is_empty()
+      && node.route_key.is_some()
+      && route_node_find_too_long_parameter_value(node, parameter_values).is_none()
+    {
+      matches.push((frame.node_index, parameter_values.clone()));
+    }
+
+    parameter_values.truncate(frame.parameter_values_length);
+    stack.pop();
+  }
+}
+
+// match the anchor, and the parameter value if the node has a parameter, against the start of
+// the path. Returns the parameter value and the rest of the path
+fn route_node_match<'r, 'f, K>(
+  node: &RouteNode<'r, K>,
+  path: &'f str,
+  anchor_index: Option<Option<usize>>,
+  maximum_parameter_value_length: Option<usize>,
+  ignore_ascii_case: bool,
+  depth: usize,
+  mut trace: Option<&mut ParseTrace<'r, K>>,
+) -> Option<(Option<&'f str>, &'f str)> {
+  if let Some(trace) = trace.as_deref_mut() {
+    trace.visit(depth, node.anchor, node.has_parameter, path);
+  }
+
+  if node.has_parameter {
+    // we are matching a parameter value! If the path's length is 0, there is no match, because a parameter value should have at least length 1
+    if path.is_empty() {
+      if let Some(trace) = trace {
+        trace.push(ParseTraceEvent::EmptyParameterValue { depth });
+      }
+      return None;
+    }
+
+    // look for the anchor in the path. If the anchor is empty, match the remainder of the path
+    let index = if node.anchor.is_empty() {
+      match maximum_parameter_value_length {
+        Some(maximum_length) if path.len() > maximum_length => {
+          if let Some(trace) = trace.as_deref_mut() {
+            trace.push(ParseTraceEvent::ParameterValueTooLong {
+              depth,
+              value_length: path.len(),
+              maximum_length,
+            });
+          }
+          None
+        }
+        _ => Some(path.len()),
+      }
+    } else {
+      // without a maximum the whole path is searched, the anchor finder keeps this linear
+      let search_length = match maximum_parameter_value_length {
+        Some(maximum_length) => {
+          floor_char_boundary(path, maximum_length.saturating_add(node.anchor.len()))
+        }
+        None => path.len(),
+      };
+      let index = match anchor_index {
+        Some(index) => index.filter(|index| index + node.anchor.len() <= search_length),
+        None if ignore_ascii_case => {
+          str_find(&path[..search_length], node.anchor, ignore_ascii_case)
+        }
+        None => node.anchor_finder.find(&path.as_bytes()[..search_length]),
+      };
+
+      if index.is_none() {
+        if let Some(trace) = trace.as_deref_mut() {
+          trace.push(ParseTraceEvent::AnchorNotFound {
+            depth,
+            searched_length: search_length,
+            limited: search_length < path.len(),
+          });
+        }
+      }
+
+      index
+    };
+
+    let index = index?;
+    let value = &path[..index];
+
+    if let Some(trace) = trace {
+      trace.push(ParseTraceEvent::AnchorFound {
+        depth,
+        value_length: index,
+      });
+    }
+
+    // remove the matches part from the path
+    Some((Some(value), &path[index + node.anchor.len()..]))
+  } else {
+    // if this node does not represent a parameter we expect the path to start with the `anchor`
+    if !str_starts_with(path, node.anchor, ignore_ascii_case) {
+      if let Some(trace) = trace {
+        trace.push(ParseTraceEvent::AnchorMismatched { depth });
+      }
+      // this node does not match the path
+      return None;
+    }
+
+    if let Some(trace) = trace {
+      trace.push(ParseTraceEvent::AnchorMatched { depth });
+    }
+
+    // we successfully matches the node to the path, now remove the matched part from the path
+    Some((None, &path[node.anchor.len()..]))
+  }
+}
+
+// scan the path once for the anchors of all children with a parameter. Returns the index of the
+// first occurrence of every anchor, in the order of the children
+fn route_node_find_parameter_anchors(
+  parameter_anchors_searcher: &AhoCorasick,
+  path: &str,
+  maximum_parameter_value_length: Option<usize>,
+) -> Vec<Option<usize>> {
+  let search_length = match maximum_parameter_value_length {
+    Some(maximum_length) => floor_char_boundary(
+      path,
+      maximum_length.saturating_add(parameter_anchors_searcher.max_pattern_len()),
+    ),
+    None => path.len(),
+  };
+
+  let mut indices = vec![None; parameter_anchors_searcher.patterns_len()];
+  let mut remaining = indices.len();
+
+  // the anchors of children with a parameter never start with the same byte, or they would
+  // have been merged into one child. So at every position at most one anchor matches, and
+  // searching again from the byte after every match finds the first occurrence of every anchor
+  let mut start = 0;
+  while remaining > 0 {
+    let input = Input::new(&path[..search_length]).span(start..search_length);
+    let Some(found) = parameter_anchors_searcher.find(input) else {
+      break;
+    };
+
+    let index = &mut indices[found.pattern().as_usize()];
+    if index.is_none() {
+      *index = Some(found.start());
+      remaining -= 1;
+    }
+
+    start = found.start() + 1;
+  }
+
+  indices
+}
+
+// check the parameter values against the maximum value lengths of the parameters of the route.
+// Returns the length of the first value that is too long, and the maximum length
+pub fn route_node_find_too_long_parameter_value<K>(
+  node: &RouteNode<'_, K>,
+  parameter_values: &[&str],
+) -> Option<(usize, usize)> {
+  node
+    .route_parameter_maximum_value_lengths
+    .iter()
+    .zip(parameter_values)
+    .find_map(|(maximum_length, parameter_value)| match maximum_length {
+      Some(maximum_length) if parameter_value.len() > *maximum_length => {
+        Some((parameter_value.len(), *maximum_length))
+      }
+      _ => None,
+    })
+}
+
+pub fn route_node_stringify<'r, 'f, K>(
+  nodes: &[RouteNode<'r, K>],
+  node_index: usize,
+  mut parameter_values: Vec<Cow<'f, str>>,
+) -> Cow<'f, str>
+where
+  'r: 'f,
+{
+  let mut current_node_index = Some(node_index);
+  let mut path_parts = Vec::new();
+
+  while let Some(node_index) = current_node_index {
+    let node = &nodes[node_index];
+    path_parts.insert(0, Cow::Borrowed(node.anchor));
+
+    if node.has_parameter {
+      // the callers check that there is a value for every parameter of the route
+      let value = parameter_values
+        .pop()
+        .expect("a value for every parameter of the route");
+      path_parts.insert(0, value);
+    }
+
+    current_node_index = node.parent;
+  }
+
+  path_parts
+    .into_iter()
+    .reduce(|path, path_part| path + path_part)
+    .unwrap_or_default()
+}
+
+// insert a route in the arena, the root node of the arena is the first node. Returns the index of
+// the leaf node of the route
+pub fn route_node_insert<'r, K: Copy>(
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  route_key: K,
+  template: &'r str,
+  route_priority: i32,
+  parameter_placeholder_re: &'r Regex,
+  ignore_ascii_case: bool,
+) -> Result<usize, InsertRouteError<K>> {
+  let template_pairs: Vec<_> = parse_template_pairs(template, parameter_placeholder_re).collect();
+  let route_parameter_names: Vec<_> = template_pairs
+    .iter()
+    .cloned()
+    .filter_map(|(_anchor, parameter)| parameter)
+    .collect();
+
+  let mut node_current_index = ROOT_NODE_INDEX;
+  for index in 0..template_pairs.len() {
+    let (anchor, parameter) = template_pairs[index];
+    let has_parameter = parameter.is_some();
+    // only the leaf node has the route, so the other nodes are the same whatever route created
+    // them
+    let (route_key, route_parameter_names) = if index == template_pairs.len() - 1 {
+      (Some(route_key), route_parameter_names.clone())
+    } else {
+      (None, Vec::new())
+    };
+
+    let (common_prefix_length, child_node_index) = route_node_find_similar_child(
+      nodes,
+      node_current_index,
+      anchor,
+      has_parameter,
+      ignore_ascii_case,
+    );
+
+    node_current_index = route_node_merge(
+      nodes,
+      node_current_index,
+      child_node_index,
+      anchor,
+      has_parameter,
+      route_key,
+      route_parameter_names,
+      common_prefix_length,
+      ignore_ascii_case,
+    )?;
+  }
+
+  {
+    let leaf_node = &mut nodes[node_current_index];
+    leaf_node.route_template = template;
+    leaf_node.route_priority = route_priority;
+  }
+  route_node_update(nodes, node_current_index, ignore_ascii_case);
+
+  Ok(node_current_index)
+}
+
+// insert an alias for a route, the parameter names are those of the route, in the order they
+// appear in the template of the alias
+pub fn route_node_insert_alias<'r, K: Copy>(
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  route_key: K,
+  template: &'r str,
+  route_priority: i32,
+  route_parameter_names: Vec<&'r str>,
+  parameter_placeholder_re: &'r Regex,
+  ignore_ascii_case: bool,
+) -> Result<usize, InsertRouteError<K>> {
+  let leaf_node_index = route_node_insert(
+    nodes,
+    route_key,
+    template,
+    route_priority,
+    parameter_placeholder_re,
+    ignore_ascii_case,
+  )?;
+
+  {
+    let leaf_node = &mut nodes[leaf_node_index];
+    leaf_node.route_alias = true;
+    leaf_node.route_parameter_names = route_parameter_names;
+  }
+
+  Ok(leaf_node_index)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::route_node::route_node_dump::route_node_dump_tree;
+  use crate::template::TEMPLATE_PLACEHOLDER_REGEX;
+  use itertools::Itertools;
+
+  #[test]
+  fn route_node_permutations() {
+    let route_configs = ["/a", "/b/{x}", "/b/{x}/", "/b/{x}/c", "/b/{y}/d"];
+
+    let mut tree_previous = None;
+    let mut nodes_previous: Option<Vec<RouteNode<_>>> = None;
+
+    for route_configs in route_configs.iter().permutations(route_configs.len()) {
+      let mut nodes = vec![RouteNode::default()];
+
+      for template in route_configs {
+        route_node_insert(
+          &mut nodes,
+          template,
+          template,
+          0,
+          &TEMPLATE_PLACEHOLDER_REGEX,
+          false,
+        )
+        .unwrap();
+      }
+
+      assert_eq!(nodes[ROOT_NODE_INDEX].children.len(), 1);
+
+      // compare the whole tree, including the order of the children
+      let mut tree = String::new();
+      route_node_dump_tree(&nodes, ROOT_NODE_INDEX, 0, &mut tree);
+      if let Some(tree_previous) = tree_previous {
+        assert_eq!(tree, tree_previous);
+      }
+      // and everything that is not in the dump
+      if let Some(nodes_previous) = nodes_previous {
+        assert!(route_node_equals(
+          &nodes,
+          ROOT_NODE_INDEX,
+          &nodes_previous,
+          ROOT_NODE_INDEX
+        ));
+      }
+
+      tree_previous = Some(tree);
+      nodes_previous = Some(nodes);
+    }
+  }
+
+  #[test]
+  fn route_node_priority_permutations() {
+    let route_configs = [
+      ("/a", 0),
+      ("/b/{x}", 1),
+      ("/b/{x}/", 0),
+      ("/b/{x}/c", -1),
+      ("/b/all", 0),
+      ("/{page}", -1),
+    ];
+
+    let mut tree_previous = None;
+
+    for route_configs in route_configs.iter().permutations(route_configs.len()) {
+      let mut nodes = vec![RouteNode::default()];
+
+      for (template, route_priority) in route_configs {
+        route_node_insert(
+          &mut nodes,
+          template,
+          template,
+          *route_priority,
+          &TEMPLATE_PLACEHOLDER_REGEX,
+          false,
+        )
+        .unwrap();
+      }
+
+      let node_root = &nodes[ROOT_NODE_INDEX];
+      assert_eq!(node_root.priority, 1);
+
+      let node_child = &nodes[node_root.children[0]];
+      assert_eq!(node_child.anchor, "/");
+
+      let priorities: Vec<_> = node_child
+        .children
+        .iter()
+        .map(|&child_node_index| nodes[child_node_index].priority)
+        .collect();
+      assert_eq!(priorities, vec![1, 0, -1]);
+
+      let leaf_node_index = route_node_parse(
+        &nodes,
+        ROOT_NODE_INDEX,
+        "/b/all",
+        None,
+        true,
+        false,
+        &mut ParseSteps::new(None),
+        &mut Vec::new(),
+        None,
+      )
+      .unwrap();
+      assert_eq!(nodes[leaf_node_index].route_key, Some(&"/b/{x}"));
+
+      // compare the whole tree, including the order of the children
+      let mut tree = String::new();
+      route_node_dump_tree(&nodes, ROOT_NODE_INDEX, 0, &mut tree);
+      if let Some(tree_previous) = tree_previous {
+        assert_eq!(tree, tree_previous);
+      }
+
+      tree_previous = Some(tree);
+    }
+  }
+
+  #[test]
+  fn route_node_ignore_ascii_case() {
+    let mut nodes = vec![RouteNode::default()];
+
+    for template in ["/product/all", "/PRODUCT/{id}", "/Product/{id}/detail"] {
+      route_node_insert(
+        &mut nodes,
+        template,
+        template,
+        0,
+        &TEMPLATE_PLACEHOLDER_REGEX,
+        true,
+      )
+      .unwrap();
+    }
+
+    let node_root = &nodes[ROOT_NODE_INDEX];
+    assert_eq!(node_root.children.len(), 1);
+
+    let node_child = &nodes[node_root.children[0]];
+    assert_eq!(node_child.anchor, "/product/");
+    assert_eq!(node_child.children.len(), 3);
+  }
+
+  #[test]
+  fn route_node_literal_children() {
+    let mut nodes = vec![RouteNode::default()];
+
+    for template in ["/a", "/A", "/\u{e9}", "/\u{e8}", "/{x}"] {
+      route_node_insert(
+        &mut nodes,
+        template,
+        template,
+        0,
+        &TEMPLATE_PLACEHOLDER_REGEX,
+        false,
+      )
+      .unwrap();
+    }
+
+    let node_child = &nodes[nodes[ROOT_NODE_INDEX].children[0]];
+    let anchors: Vec<_> = node_child
+      .children
+      .iter()
+      .map(|&child_node_index| nodes[child_node_index].anchor)
+      .collect();
+    assert_eq!(anchors, vec!["\u{e8}", "\u{e9}", "A", "a", ""]);
+
+    // the first byte is looked up without regard to ascii case, and is shared by multi byte chars
+    let candidates = |path| route_node_candidate_children(node_child, path).collect::<Vec<_>>();
+    assert_eq!(candidates("a"), vec![2, 3, 4]);
+    assert_eq!(candidates("A"), vec![2, 3, 4]);
+    assert_eq!(candidates("\u{e9}"), vec![0, 1, 4]);
+    assert_eq!(candidates("b"), vec![4]);
+    assert_eq!(candidates(""), vec![4]);
+  }
+}
@@ -0,0 +1,176 @@
+use super::*;
+use std::fmt::{Debug, Write};
+
+// write the node, and all of its descendants, as an indented tree. Every line has the anchor of
+// a node, prefixed by `{}` if the node has a parameter, followed by the key of the route (if any)
+pub fn route_node_dump_tree<K: Debug>(
+  nodes: &[RouteNode<'_, K>],
+  node_index: usize,
+  depth: usize,
+  output: &mut String,
+) {
+  let mut stack = vec![(node_index, depth)];
+
+  while let Some((node_index, depth)) = stack.pop() {
+    let node = &nodes[node_index];
+
+    write!(output, "{:indent$}", "", indent = depth * 2).unwrap();
+    output.push_str(&route_node_label(node, " "));
+    output.push('\n');
+
+    // reversed, so the first child is written first
+    stack.extend(
+      node
+        .children
+        .iter()
+        .rev()
+        .map(|&child_node_index| (child_node_index, depth + 1)),
+    );
+  }
+}
+
+// what is left to write of the graph, a node with its descendants or the edge from a parent to a
+// node after the descendants of the node
+enum DotStep {
+  Node {
+    node_index: usize,
+    parent_id: Option<usize>,
+  },
+  Edge {
+    parent_id: usize,
+    id: usize,
+  },
+}
+
+// write the node, and all of its descendants, as graphviz nodes and edges. Returns the id of the
+// node
+pub fn route_node_dump_dot<K: Debug>(
+  nodes: &[RouteNode<'_, K>],
+  node_index: usize,
+  next_id: &mut usize,
+  output: &mut String,
+) -> usize {
+  let root_id = *next_id;
+  let mut stack = vec![DotStep::Node {
+    node_index,
+    parent_id: None,
+  }];
+
+  while let Some(step) = stack.pop() {
+    let (node_index, parent_id) = match step {
+      DotStep::Node {
+        node_index,
+        parent_id,
+      } => (node_index, parent_id),
+      DotStep::Edge { parent_id, id } => {
+        writeln!(output, "  n{} -> n{};", parent_id, id).unwrap();
+        continue;
+      }
+    };
+    let node = &nodes[node_index];
+
+    let id = *next_id;
+    *next_id += 1;
+
+    let label = route_node_label(node, "\n")
+      .replace('\\', "\\\\")
+      .replace('"', "\\\"")
+      .replace('\n', "\\n");
+    let shape = if node.route_key.is_some() {
+      "doubleoctagon"
+    } else {
+      "box"
+    };
+    writeln!(output, "  n{} [label=\"{}\", shape={}];", id, label, shape).unwrap();
+
+    if let Some(parent_id) = parent_id {
+      stack.push(DotStep::Edge { parent_id, id });
+    }
+    // reversed, so the first child is written first
+    stack.extend(
+      node
+        .children
+        .iter()
+        .rev()
+        .map(|&child_node_index| DotStep::Node {
+          node_index: child_node_index,
+          parent_id: Some(id),
+        }),
+    );
+  }
+
+  root_id
+}
+
+fn route_node_label<K: Debug>(node: &RouteNode<'_, K>, separator: &str) -> String {
+  let mut label = String::new();
+
+  if node.has_parameter {
+    label.push_str("{} ");
+  }
+  write!(label, "{:?}", node.anchor).unwrap();
+
+  if let Some(route_key) = &node.route_key {
+    write!(label, "{}=> {:?}", separator, route_key).unwrap();
+    if node.route_alias {
+      label.push_str(" (alias)");
+    }
+    if node.route_priority != 0 {
+      write!(label, " (priority {})", node.route_priority).unwrap();
+    }
+  }
+
+  label
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::route_node::route_node_arena::route_node_insert;
+  use crate::template::TEMPLATE_PLACEHOLDER_REGEX;
+
+  #[test]
+  fn route_node_dump() {
+    let mut nodes = vec![RouteNode::default()];
+
+    for template in ["/product/all", "/product/{id}", "/product/{id}/detail"] {
+      route_node_insert(
+        &mut nodes,
+        template,
+        template,
+        0,
+        &TEMPLATE_PLACEHOLDER_REGEX,
+        false,
+      )
+      .unwrap();
+    }
+
+    let mut tree = String::new();
+    route_node_dump_tree(&nodes, ROOT_NODE_INDEX, 0, &mut tree);
+    assert_eq!(
+      tree,
+      r#"""
+  "/product/"
+    {} "/detail" => "/product/{id}/detail"
+    "all" => "/product/all"
+    {} "" => "/product/{id}"
+"#
+    );
+
+    let mut dot = String::new();
+    route_node_dump_dot(&nodes, ROOT_NODE_INDEX, &mut 0, &mut dot);
+    assert_eq!(
+      dot,
+      r#"  n0 [label="\"\"", shape=box];
+  n1 [label="\"/product/\"", shape=box];
+  n2 [label="{} \"/detail\"\n=> \"/product/{id}/detail\"", shape=doubleoctagon];
+  n1 -> n2;
+  n3 [label="\"all\"\n=> \"/product/all\"", shape=doubleoctagon];
+  n1 -> n3;
+  n4 [label="{} \"\"\n=> \"/product/{id}\"", shape=doubleoctagon];
+  n1 -> n4;
+  n0 -> n1;
+"#
+    );
+  }
+}
@@ -1,95 +1,106 @@
 use super::*;
-use std::{cell::RefCell, rc::Rc};
-
-pub fn route_node_merge<'r, K>(
-  parent_node_rc: RouteNodeRc<'r, K>,
-  child_node_rc: Option<RouteNodeRc<'r, K>>,
+use crate::error::InsertRouteError;
+use crate::string_utility::str_equals;
+
+#[allow(clippy::too_many_arguments)]
+pub fn route_node_merge<'r, K: Copy>(
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  parent_node_index: usize,
+  child_node_index: Option<usize>,
   anchor: &'r str,
   has_parameter: bool,
   route_key: Option<K>,
   route_parameter_names: Vec<&'r str>,
   common_prefix_length: usize,
-) -> RouteNodeRc<'r, K> {
-  if let Some(child_node_rc) = child_node_rc {
-    let common_prefix = &anchor[..common_prefix_length];
-    let child_anchor = child_node_rc.borrow().anchor;
-
-    if child_anchor == anchor {
-      return route_node_merge_join(child_node_rc, route_key, route_parameter_names.clone());
-    } else if child_anchor == common_prefix {
-      return route_node_merge_add_to_child(
-        parent_node_rc,
-        child_node_rc,
+  ignore_ascii_case: bool,
+) -> Result<usize, InsertRouteError<K>> {
+  if let Some(child_node_index) = child_node_index {
+    let child_anchor = nodes[child_node_index].anchor;
+
+    if str_equals(child_anchor, anchor, ignore_ascii_case) {
+      route_node_merge_join(nodes, child_node_index, route_key, route_parameter_names)
+    } else if child_anchor.len() == common_prefix_length {
+      route_node_merge_add_to_child(
+        nodes,
+        parent_node_index,
+        child_node_index,
         anchor,
         has_parameter,
         route_key,
-        route_parameter_names.clone(),
+        route_parameter_names,
         common_prefix_length,
-      );
-    } else if anchor == common_prefix {
-      return route_node_merge_add_to_new(
-        parent_node_rc,
-        child_node_rc,
+        ignore_ascii_case,
+      )
+    } else if anchor.len() == common_prefix_length {
+      Ok(route_node_merge_add_to_new(
+        nodes,
+        parent_node_index,
+        child_node_index,
         anchor,
         has_parameter,
         route_key,
-        route_parameter_names.clone(),
+        route_parameter_names,
         common_prefix_length,
-      );
+      ))
     } else {
-      return route_node_merge_intermediate(
-        parent_node_rc,
-        child_node_rc,
+      Ok(route_node_merge_intermediate(
+        nodes,
+        parent_node_index,
+        child_node_index,
         anchor,
         has_parameter,
         route_key,
-        route_parameter_names.clone(),
+        route_parameter_names,
         common_prefix_length,
-      );
+      ))
     }
   } else {
-    return route_node_merge_new(
-      parent_node_rc,
+    Ok(route_node_merge_new(
+      nodes,
+      parent_node_index,
       anchor,
       has_parameter,
       route_key,
-      route_parameter_names.clone(),
-    );
+      route_parameter_names,
+    ))
   }
 }
 
 fn route_node_merge_new<'r, K>(
-  parent_node_rc: RouteNodeRc<'r, K>,
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  parent_node_index: usize,
   anchor: &'r str,
   has_parameter: bool,
   route_key: Option<K>,
   route_parameter_names: Vec<&'r str>,
-) -> RouteNodeRc<'r, K> {
+) -> usize {
   let new_node = RouteNode::<K> {
     anchor,
     has_parameter,
     route_key,
     route_parameter_names,
-    parent: Some(Rc::downgrade(&parent_node_rc)),
     ..Default::default()
   };
 
-  let node_new_rc = Rc::new(RefCell::new(new_node));
-  let mut parent_node = parent_node_rc.borrow_mut();
-  parent_node.children.insert(node_new_rc.clone());
+  let new_node_index = nodes.len();
+  nodes.push(new_node);
+  route_node_add_child(nodes, parent_node_index, new_node_index);
 
-  node_new_rc
+  new_node_index
 }
 
-fn route_node_merge_join<'r, K>(
-  child_node_rc: RouteNodeRc<'r, K>,
+fn route_node_merge_join<'r, K: Copy>(
+  nodes: &mut [RouteNode<'r, K>],
+  child_node_index: usize,
   route_key: Option<K>,
   route_parameter_names: Vec<&'r str>,
-) -> RouteNodeRc<'r, K> {
-  let mut child_node = child_node_rc.borrow_mut();
+) -> Result<usize, InsertRouteError<K>> {
+  let child_node = &mut nodes[child_node_index];
 
-  if child_node.route_key.is_some() && route_key.is_some() {
-    panic!("ambiguous route")
+  if let (Some(child_route_key), Some(_)) = (child_node.route_key, route_key) {
+    return Err(InsertRouteError::AmbiguousRoute {
+      route_key: child_route_key,
+    });
   }
 
   if child_node.route_key.is_none() {
@@ -97,110 +108,111 @@ fn route_node_merge_join<'r, K>(
     child_node.route_parameter_names = route_parameter_names;
   }
 
-  child_node_rc.clone()
+  Ok(child_node_index)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn route_node_merge_intermediate<'r, K>(
-  parent_node_rc: RouteNodeRc<'r, K>,
-  child_node_rc: RouteNodeRc<'r, K>,
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  parent_node_index: usize,
+  child_node_index: usize,
   anchor: &'r str,
   has_parameter: bool,
   route_key: Option<K>,
   route_parameter_names: Vec<&'r str>,
   common_prefix_length: usize,
-) -> RouteNodeRc<'r, K> {
-  let new_node = RouteNode {
-    anchor,
-    has_parameter,
-    route_key,
-    route_parameter_names,
-    ..Default::default()
-  };
-
-  let new_node_rc = Rc::new(RefCell::new(new_node));
+) -> usize {
+  let child_anchor = nodes[child_node_index].anchor;
 
   // remove the child from parent
-  {
-    let mut parent_node = parent_node_rc.borrow_mut();
-    parent_node.children.remove(&child_node_rc);
-  }
-
-  // create an intermediate node
-  let intermediate_node_rc = {
-    let child_node = child_node_rc.borrow();
-
-    let mut intermediate_node = RouteNode {
-      anchor: &child_node.anchor[..common_prefix_length],
-      has_parameter: child_node.has_parameter,
-      parent: Some(Rc::downgrade(&parent_node_rc)),
-      ..Default::default()
-    };
-
-    intermediate_node.children.insert(child_node_rc.clone());
-    intermediate_node.children.insert(new_node_rc.clone());
+  route_node_remove_child(nodes, parent_node_index, child_node_index);
 
-    // insert the intermediate node
-    let mut parent_node = parent_node_rc.borrow_mut();
-
-    let intermediate_node_rc = Rc::new(RefCell::new(intermediate_node));
-    parent_node.children.insert(intermediate_node_rc.clone());
+  // create and insert an intermediate node
+  let intermediate_node = RouteNode {
+    anchor: &child_anchor[..common_prefix_length],
+    has_parameter,
+    ..Default::default()
+  };
+  let intermediate_node_index = nodes.len();
+  nodes.push(intermediate_node);
+  route_node_add_child(nodes, parent_node_index, intermediate_node_index);
 
-    intermediate_node_rc
+  // the new and child nodes get the rest of their anchors, and become children of the
+  // intermediate node
+  let new_node = RouteNode {
+    anchor: &anchor[common_prefix_length..],
+    has_parameter: false,
+    route_key,
+    route_parameter_names,
+    ..Default::default()
   };
+  let new_node_index = nodes.len();
+  nodes.push(new_node);
+  route_node_add_child(nodes, intermediate_node_index, new_node_index);
 
-  // update the new and child nodes
   {
-    let mut child_node = child_node_rc.borrow_mut();
-    let mut new_node = new_node_rc.borrow_mut();
-
-    new_node.parent = Some(Rc::downgrade(&intermediate_node_rc));
-    new_node.anchor = &new_node.anchor[common_prefix_length..];
-    new_node.has_parameter = false;
-
-    child_node.parent = Some(Rc::downgrade(&intermediate_node_rc));
-    child_node.anchor = &child_node.anchor[common_prefix_length..];
+    let child_node = &mut nodes[child_node_index];
+    child_node.anchor = &child_anchor[common_prefix_length..];
     child_node.has_parameter = false;
   }
+  route_node_add_child(nodes, intermediate_node_index, child_node_index);
 
-  // return rc to the new node
-  new_node_rc.clone()
+  new_node_index
 }
 
-fn route_node_merge_add_to_child<'r, K>(
-  _parent_node_rc: RouteNodeRc<'r, K>,
-  child_node_rc: RouteNodeRc<'r, K>,
+#[allow(clippy::too_many_arguments)]
+fn route_node_merge_add_to_child<'r, K: Copy>(
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  _parent_node_index: usize,
+  child_node_index: usize,
   anchor: &'r str,
   _has_parameter: bool,
   route_key: Option<K>,
   route_parameter_names: Vec<&'r str>,
   common_prefix_length: usize,
-) -> RouteNodeRc<'r, K> {
+  ignore_ascii_case: bool,
+) -> Result<usize, InsertRouteError<K>> {
   let anchor = &anchor[common_prefix_length..];
   let has_parameter = false;
 
-  let (common_prefix_length2, child_node_rc2) =
-    route_node_find_similar_child(&child_node_rc.borrow(), anchor, has_parameter);
+  let (common_prefix_length2, child_node_index2) = route_node_find_similar_child(
+    nodes,
+    child_node_index,
+    anchor,
+    has_parameter,
+    ignore_ascii_case,
+  );
 
-  return route_node_merge(
-    child_node_rc.clone(),
-    child_node_rc2,
+  route_node_merge(
+    nodes,
+    child_node_index,
+    child_node_index2,
     anchor,
     has_parameter,
     route_key,
     route_parameter_names,
     common_prefix_length2,
-  );
+    ignore_ascii_case,
+  )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn route_node_merge_add_to_new<'r, K>(
-  parent_node_rc: RouteNodeRc<'r, K>,
-  child_node_rc: RouteNodeRc<'r, K>,
+  nodes: &mut Vec<RouteNode<'r, K>>,
+  parent_node_index: usize,
+  child_node_index: usize,
   anchor: &'r str,
   has_parameter: bool,
   route_key: Option<K>,
   route_parameter_names: Vec<&'r str>,
   common_prefix_length: usize,
-) -> RouteNodeRc<'r, K> {
+) -> usize {
+  // take the anchor from the existing child, the anchors are only different when ignoring case
+  let child_anchor = nodes[child_node_index].anchor;
+  let anchor = &child_anchor[..anchor.len()];
+
+  route_node_remove_child(nodes, parent_node_index, child_node_index);
+
   let new_node = RouteNode {
     anchor,
     has_parameter,
@@ -208,21 +220,16 @@ fn route_node_merge_add_to_new<'r, K>(
     route_parameter_names,
     ..Default::default()
   };
-  let new_node_rc = Rc::new(RefCell::new(new_node));
-
-  let mut parent_node = parent_node_rc.borrow_mut();
-
-  parent_node.children.remove(&child_node_rc);
-  parent_node.children.insert(new_node_rc.clone());
+  let new_node_index = nodes.len();
+  nodes.push(new_node);
+  route_node_add_child(nodes, parent_node_index, new_node_index);
 
-  let mut new_node = new_node_rc.borrow_mut();
-  new_node.children.insert(child_node_rc.clone());
-  new_node.parent = Some(Rc::downgrade(&parent_node_rc));
-
-  let mut child_node = child_node_rc.borrow_mut();
-  child_node.anchor = &child_node.anchor[common_prefix_length..];
-  child_node.has_parameter = false;
-  child_node.parent = Some(Rc::downgrade(&new_node_rc));
+  {
+    let child_node = &mut nodes[child_node_index];
+    child_node.anchor = &child_anchor[common_prefix_length..];
+    child_node.has_parameter = false;
+  }
+  route_node_add_child(nodes, new_node_index, child_node_index);
 
-  new_node_rc.clone()
+  new_node_index
 }
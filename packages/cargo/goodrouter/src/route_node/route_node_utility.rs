@@ -1,28 +1,330 @@
 use super::*;
 use crate::string_utility::find_common_prefix_length;
+use aho_corasick::MatchKind;
+use std::hash::{Hash, Hasher};
 
 pub fn route_node_find_similar_child<'r, K>(
-  parent_node: &RouteNode<'r, K>,
+  nodes: &[RouteNode<'r, K>],
+  parent_node_index: usize,
   anchor: &'r str,
   has_parameter: bool,
-) -> (usize, Option<RouteNodeRc<'r, K>>) {
-  let anchor_chars: Vec<_> = anchor.chars().collect();
+  ignore_ascii_case: bool,
+) -> (usize, Option<usize>) {
+  let parent_node = &nodes[parent_node_index];
 
-  for child_node_rc in parent_node.children.iter() {
-    if child_node_rc.borrow().has_parameter != has_parameter {
+  // only a literal child that starts with the same byte can have a common prefix with a non
+  // empty literal anchor, all other children are in the other children
+  let (literal_children, other_children) = match anchor.as_bytes().first() {
+    Some(first_byte) if !has_parameter => (
+      route_node_find_literal_children(parent_node, *first_byte),
+      &[][..],
+    ),
+    _ => (&[][..], &parent_node.other_children[..]),
+  };
+
+  for child_position in literal_children
+    .iter()
+    .map(|(_first_byte, position)| *position)
+    .chain(other_children.iter().copied())
+  {
+    let child_node_index = parent_node.children[child_position];
+    let child_node = &nodes[child_node_index];
+
+    if child_node.has_parameter != has_parameter {
       continue;
     }
 
-    let child_anchor_chars: Vec<_> = child_node_rc.borrow().anchor.chars().collect();
+    let common_prefix_length =
+      find_common_prefix_length(anchor, child_node.anchor, ignore_ascii_case);
 
-    let common_prefix_length = find_common_prefix_length(&anchor_chars, &child_anchor_chars);
-
-    if common_prefix_length == 0 {
+    // empty anchors have no common prefix, but they are still the same
+    if common_prefix_length == 0 && !(anchor.is_empty() && child_node.anchor.is_empty()) {
       continue;
     }
 
-    return (common_prefix_length, Some(child_node_rc.clone()));
+    return (common_prefix_length, Some(child_node_index));
   }
 
   Default::default()
 }
+
+// add a child to the children of the parent, at its place in the order of the nodes
+pub fn route_node_add_child<K>(
+  nodes: &mut [RouteNode<'_, K>],
+  parent_node_index: usize,
+  child_node_index: usize,
+) {
+  let position = nodes[parent_node_index]
+    .children
+    .partition_point(|&index| nodes[index] <= nodes[child_node_index]);
+  let literal_first_byte = route_node_literal_first_byte(&nodes[child_node_index]);
+  nodes[child_node_index].parent = Some(parent_node_index);
+
+  let parent_node = &mut nodes[parent_node_index];
+  parent_node.children.insert(position, child_node_index);
+
+  // the children after the new child move one position
+  for (_first_byte, child_position) in parent_node.literal_children.iter_mut() {
+    if *child_position >= position {
+      *child_position += 1;
+    }
+  }
+  for child_position in parent_node.other_children.iter_mut() {
+    if *child_position >= position {
+      *child_position += 1;
+    }
+  }
+
+  if let Some(first_byte) = literal_first_byte {
+    let index = parent_node
+      .literal_children
+      .partition_point(|entry| *entry < (first_byte, position));
+    parent_node
+      .literal_children
+      .insert(index, (first_byte, position));
+  } else {
+    let index = parent_node
+      .other_children
+      .partition_point(|child_position| *child_position < position);
+    parent_node.other_children.insert(index, position);
+  }
+}
+
+pub fn route_node_remove_child<K>(
+  nodes: &mut [RouteNode<'_, K>],
+  parent_node_index: usize,
+  child_node_index: usize,
+) {
+  let parent_node = &mut nodes[parent_node_index];
+  let Some(position) = parent_node
+    .children
+    .iter()
+    .position(|&index| index == child_node_index)
+  else {
+    return;
+  };
+  parent_node.children.remove(position);
+
+  // the children after the removed child move one position back
+  parent_node
+    .literal_children
+    .retain(|(_first_byte, child_position)| *child_position != position);
+  for (_first_byte, child_position) in parent_node.literal_children.iter_mut() {
+    if *child_position > position {
+      *child_position -= 1;
+    }
+  }
+  parent_node
+    .other_children
+    .retain(|child_position| *child_position != position);
+  for child_position in parent_node.other_children.iter_mut() {
+    if *child_position > position {
+      *child_position -= 1;
+    }
+  }
+}
+
+// the positions of the children that may match a path, in the order of the children. These are
+// the literal children that start with the first byte of the path and all other children
+pub fn route_node_candidate_children<'n, K>(
+  node: &'n RouteNode<'_, K>,
+  path: &str,
+) -> CandidateChildren<'n> {
+  let literal_children = match path.as_bytes().first() {
+    Some(first_byte) => route_node_find_literal_children(node, *first_byte),
+    None => &[],
+  };
+
+  CandidateChildren {
+    literal_children,
+    other_children: &node.other_children,
+  }
+}
+
+// merges the literal children and the other children that are left, by position
+pub struct CandidateChildren<'n> {
+  literal_children: &'n [(u8, usize)],
+  other_children: &'n [usize],
+}
+
+impl<'n> Iterator for CandidateChildren<'n> {
+  type Item = usize;
+
+  fn next(&mut self) -> Option<usize> {
+    match (self.literal_children.first(), self.other_children.first()) {
+      (Some((_first_byte, literal_position)), Some(other_position))
+        if literal_position < other_position =>
+      {
+        self.literal_children = &self.literal_children[1..];
+        Some(*literal_position)
+      }
+      (_, Some(other_position)) => {
+        self.other_children = &self.other_children[1..];
+        Some(*other_position)
+      }
+      (Some((_first_byte, literal_position)), None) => {
+        self.literal_children = &self.literal_children[1..];
+        Some(*literal_position)
+      }
+      (None, None) => None,
+    }
+  }
+}
+
+// the literal children with an anchor that starts with the byte, without regard to ascii case
+fn route_node_find_literal_children<'n, K>(
+  node: &'n RouteNode<'_, K>,
+  first_byte: u8,
+) -> &'n [(u8, usize)] {
+  let first_byte = first_byte.to_ascii_lowercase();
+  let start = node
+    .literal_children
+    .partition_point(|(child_first_byte, _position)| *child_first_byte < first_byte);
+  let end = node
+    .literal_children
+    .partition_point(|(child_first_byte, _position)| *child_first_byte <= first_byte);
+
+  &node.literal_children[start..end]
+}
+
+// the key of a node in the literal children of its parent, if it is a literal child
+fn route_node_literal_first_byte<K>(node: &RouteNode<'_, K>) -> Option<u8> {
+  if node.has_parameter {
+    return None;
+  }
+
+  node
+    .anchor
+    .as_bytes()
+    .first()
+    .map(|first_byte| first_byte.to_ascii_lowercase())
+}
+
+// update the node and its ancestors after an insert. The anchors of these nodes and their
+// children may have been split, so their anchor finders and parameter anchors searchers are
+// created again. The priority is set to the highest priority of the routes of the node and its
+// descendants, this priority is part of the ordering of nodes, so when the priority changes a
+// node is removed from the children of its parent first, and then added again
+pub fn route_node_update<K>(
+  nodes: &mut [RouteNode<'_, K>],
+  node_index: usize,
+  ignore_ascii_case: bool,
+) {
+  let mut current_node_index = Some(node_index);
+
+  while let Some(node_index) = current_node_index {
+    nodes[node_index].anchor_finder = Finder::new(nodes[node_index].anchor);
+    for position in 0..nodes[node_index].children.len() {
+      let child_node_index = nodes[node_index].children[position];
+      nodes[child_node_index].anchor_finder = Finder::new(nodes[child_node_index].anchor);
+    }
+
+    let node = &nodes[node_index];
+    let parameter_anchors: Vec<_> = node
+      .children
+      .iter()
+      .map(|&child_node_index| &nodes[child_node_index])
+      .filter(|child_node| child_node.has_parameter && !child_node.anchor.is_empty())
+      .map(|child_node| child_node.anchor)
+      .collect();
+    // without a searcher the anchor of every parameter child is searched for on its own
+    let parameter_anchors_searcher = if ignore_ascii_case && parameter_anchors.len() > 1 {
+      AhoCorasick::builder()
+        .match_kind(MatchKind::LeftmostFirst)
+        .ascii_case_insensitive(ignore_ascii_case)
+        .build(parameter_anchors)
+        .ok()
+    } else {
+      None
+    };
+
+    let priority = node
+      .children
+      .iter()
+      .map(|&child_node_index| nodes[child_node_index].priority)
+      .chain(node.route_key.as_ref().map(|_| node.route_priority))
+      .max()
+      .unwrap_or_default();
+    let parent_node_index = node.parent;
+
+    nodes[node_index].parameter_anchors_searcher = parameter_anchors_searcher;
+    match parent_node_index {
+      Some(parent_node_index) if nodes[node_index].priority != priority => {
+        route_node_remove_child(nodes, parent_node_index, node_index);
+        nodes[node_index].priority = priority;
+        route_node_add_child(nodes, parent_node_index, node_index);
+      }
+      _ => nodes[node_index].priority = priority,
+    }
+
+    current_node_index = parent_node_index;
+  }
+}
+
+// compare two tries of nodes, that may be in different arenas. Nodes are the same if they have
+// the same anchor, parameter and routes, and their children are the same, in the same order. The
+// position of the nodes in the arena does not matter
+pub fn route_node_equals<K: PartialEq>(
+  nodes: &[RouteNode<'_, K>],
+  node_index: usize,
+  other_nodes: &[RouteNode<'_, K>],
+  other_node_index: usize,
+) -> bool {
+  let mut stack = vec![(node_index, other_node_index)];
+
+  while let Some((node_index, other_node_index)) = stack.pop() {
+    let node = &nodes[node_index];
+    let other_node = &other_nodes[other_node_index];
+
+    if node.anchor != other_node.anchor
+      || node.has_parameter != other_node.has_parameter
+      || node.priority != other_node.priority
+      || node.route_key != other_node.route_key
+      || node.route_parameter_names != other_node.route_parameter_names
+      || node.route_template != other_node.route_template
+      || node.route_alias != other_node.route_alias
+      || node.route_priority != other_node.route_priority
+      || node.route_parameter_maximum_value_lengths
+        != other_node.route_parameter_maximum_value_lengths
+      || node.children.len() != other_node.children.len()
+    {
+      return false;
+    }
+
+    stack.extend(
+      node
+        .children
+        .iter()
+        .copied()
+        .zip(other_node.children.iter().copied()),
+    );
+  }
+
+  true
+}
+
+// hash the trie of nodes, consistent with `route_node_equals`
+pub fn route_node_hash<K: Hash, H: Hasher>(
+  nodes: &[RouteNode<'_, K>],
+  node_index: usize,
+  state: &mut H,
+) {
+  let mut stack = vec![node_index];
+
+  while let Some(node_index) = stack.pop() {
+    let node = &nodes[node_index];
+
+    node.anchor.hash(state);
+    node.has_parameter.hash(state);
+    node.priority.hash(state);
+    node.route_key.hash(state);
+    node.route_parameter_names.hash(state);
+    node.route_template.hash(state);
+    node.route_alias.hash(state);
+    node.route_priority.hash(state);
+    node.route_parameter_maximum_value_lengths.hash(state);
+    node.children.len().hash(state);
+
+    stack.extend(node.children.iter().rev().copied());
+  }
+}
@@ -1,52 +1,222 @@
 use crate::{
-  route_node::route_node_rc::{route_node_insert, route_node_parse, route_node_stringify},
-  route_node::RouteNodeRc,
-  template::TEMPLATE_PLACEHOLDER_REGEX,
+  error::{InsertRouteError, ParameterValueDecodeError, ParseRouteError, StringifyRouteError},
+  parse_trace::ParseTrace,
+  path_normalization::{
+    collapse_duplicate_slashes, remove_dot_segments, toggle_trailing_slash, TrailingSlashPolicy,
+  },
+  route_node::route_node_arena::{
+    route_node_insert, route_node_insert_alias, route_node_parse, route_node_parse_all,
+    route_node_stringify, ParseSteps,
+  },
+  route_node::route_node_dump::{route_node_dump_dot, route_node_dump_tree},
+  route_node::route_node_utility::{route_node_equals, route_node_hash},
+  route_node::{RouteNode, ROOT_NODE_INDEX},
+  string_utility::normalize_unicode,
+  template::{template_pairs::parse_template_pairs, TEMPLATE_PLACEHOLDER_REGEX},
 };
 use regex::Regex;
-use std::hash::Hash;
-use std::{borrow::Cow, collections::HashMap};
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use unicode_normalization::is_nfc;
 
-type ParameterValueEncoder = dyn Fn(&str) -> Cow<str>;
-type ParameterValueDecoder = dyn Fn(&str) -> Cow<str>;
+type ParameterValueEncoder = dyn Fn(&str) -> Cow<'_, str> + Send + Sync;
+type ParameterValueDecoder =
+  dyn Fn(&str) -> Result<Cow<'_, str>, ParameterValueDecodeError> + Send + Sync;
 
+pub type RouteParameters<'r, 'f> = HashMap<&'r str, Cow<'f, str>>;
+
+#[derive(Debug)]
+pub struct RouteMatch<'r, 'f, K> {
+  pub route_key: K,
+  pub route_parameters: RouteParameters<'r, 'f>,
+  // the normalized path that matched the route
+  pub canonical_path: Cow<'f, str>,
+  // if the trailing slash policy is redirect, this is true when the canonical path is not the same
+  // as the path that was requested
+  pub redirect: bool,
+  // true if the path matched an alias of the route
+  pub alias: bool,
+}
+
+// the trie of route nodes of a router, tries are equal and hash the same when they have the same
+// nodes with the same routes. Debug formats the trie like `dump_tree`
+#[derive(Clone, Copy)]
+pub struct RouteTrie<'a, 'r, K> {
+  nodes: &'a [RouteNode<'r, K>],
+}
+
+impl<'a, 'r, K: PartialEq> PartialEq for RouteTrie<'a, 'r, K> {
+  fn eq(&self, other: &Self) -> bool {
+    route_node_equals(self.nodes, ROOT_NODE_INDEX, other.nodes, ROOT_NODE_INDEX)
+  }
+}
+
+impl<'a, 'r, K: Eq> Eq for RouteTrie<'a, 'r, K> {}
+
+impl<'a, 'r, K: Hash> Hash for RouteTrie<'a, 'r, K> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    route_node_hash(self.nodes, ROOT_NODE_INDEX, state);
+  }
+}
+
+impl<'a, 'r, K: Debug> Debug for RouteTrie<'a, 'r, K> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut output = String::new();
+    route_node_dump_tree(self.nodes, ROOT_NODE_INDEX, 0, &mut output);
+    f.write_str(&output)
+  }
+}
+
+// the router is `Send` and `Sync` if the route key is, cloning it copies the route nodes but
+// shares the parameter value encoder and decoder
+#[derive(Clone)]
 pub struct Router<'r, K> {
-  root_node_rc: RouteNodeRc<'r, K>,
-  leaf_nodes_rc: HashMap<K, RouteNodeRc<'r, K>>,
-  maximum_parameter_value_length: usize,
+  // the arena of route nodes, the first node is the root
+  nodes: Vec<RouteNode<'r, K>>,
+  // index of the leaf node of every route in the arena
+  leaf_node_indices: HashMap<K, usize>,
+  route_keys: Vec<K>,
+  maximum_parameter_value_length: Option<usize>,
+  maximum_parse_steps: Option<usize>,
+  parameter_maximum_value_lengths: HashMap<&'r str, usize>,
+  ignore_ascii_case: bool,
+  normalize_unicode: bool,
+  collapse_duplicate_slashes: bool,
+  remove_dot_segments: bool,
+  trailing_slash_policy: TrailingSlashPolicy,
   parameter_placeholder_re: &'r Regex,
-  parameter_value_encoder: Box<ParameterValueEncoder>,
-  parameter_value_decoder: Box<ParameterValueDecoder>,
+  parameter_value_encoder: Arc<ParameterValueEncoder>,
+  parameter_value_decoder: Arc<ParameterValueDecoder>,
 }
 
 impl<'r, K: Eq + Hash + Copy> Router<'r, K> {
   pub fn new() -> Self {
-    fn parameter_encoder(value: &str) -> Cow<str> {
+    fn parameter_encoder(value: &str) -> Cow<'_, str> {
       urlencoding::encode(value)
     }
-    fn parameter_decoder(value: &str) -> Cow<str> {
-      urlencoding::decode(value).unwrap_or(Cow::Borrowed(value))
+    fn parameter_decoder(value: &str) -> Result<Cow<'_, str>, ParameterValueDecodeError> {
+      Ok(urlencoding::decode(value)?)
     }
 
-    let parameter_value_encoder = Box::new(parameter_encoder);
-    let parameter_value_decoder = Box::new(parameter_decoder);
+    let parameter_value_encoder = Arc::new(parameter_encoder);
+    let parameter_value_decoder = Arc::new(parameter_decoder);
 
     Self {
-      root_node_rc: RouteNodeRc::default(),
-      leaf_nodes_rc: HashMap::new(),
-      maximum_parameter_value_length: 50,
+      nodes: vec![RouteNode::default()],
+      leaf_node_indices: HashMap::new(),
+      route_keys: Vec::new(),
+      maximum_parameter_value_length: None,
+      maximum_parse_steps: None,
+      parameter_maximum_value_lengths: HashMap::new(),
+      ignore_ascii_case: false,
+      normalize_unicode: false,
+      collapse_duplicate_slashes: false,
+      remove_dot_segments: false,
+      trailing_slash_policy: TrailingSlashPolicy::Strict,
       parameter_placeholder_re: &TEMPLATE_PLACEHOLDER_REGEX,
       parameter_value_encoder,
       parameter_value_decoder,
     }
   }
 
-  pub fn set_maximum_parameter_value_length(&mut self, value: usize) -> &mut Self {
-    self.maximum_parameter_value_length = value;
+  // limit the length of every parameter value, in bytes, there is no limit by default
+  pub fn set_maximum_parameter_value_length(
+    &mut self,
+    value: impl Into<Option<usize>>,
+  ) -> &mut Self {
+    self.maximum_parameter_value_length = value.into();
+
+    self
+  }
+
+  // limit the number of route nodes that are matched when parsing a path
+  pub fn set_maximum_parse_steps(&mut self, value: impl Into<Option<usize>>) -> &mut Self {
+    self.maximum_parse_steps = value.into();
+
+    self
+  }
+
+  // limit the length of the values of every parameter with this name, in bytes
+  pub fn set_parameter_maximum_value_length(
+    &mut self,
+    parameter_name: &'r str,
+    value: impl Into<Option<usize>>,
+  ) -> &mut Self {
+    if let Some(value) = value.into() {
+      self
+        .parameter_maximum_value_lengths
+        .insert(parameter_name, value);
+    } else {
+      self.parameter_maximum_value_lengths.remove(parameter_name);
+    }
+
+    // the routes that were already inserted, and their aliases, get the limit too
+    let value = self
+      .parameter_maximum_value_lengths
+      .get(parameter_name)
+      .copied();
+    for node in &mut self.nodes {
+      for (route_parameter_name, route_parameter_maximum_value_length) in node
+        .route_parameter_names
+        .iter()
+        .zip(node.route_parameter_maximum_value_lengths.iter_mut())
+      {
+        if *route_parameter_name == parameter_name {
+          *route_parameter_maximum_value_length = value;
+        }
+      }
+    }
+
+    self
+  }
+
+  // match anchors without regard to ascii case, panics if routes were inserted before
+  pub fn set_ignore_ascii_case(&mut self, value: bool) -> &mut Self {
+    // the routes in the trie were merged with the old setting
+    assert!(
+      self.leaf_node_indices.is_empty(),
+      "set ignore ascii case before inserting routes"
+    );
+    self.ignore_ascii_case = value;
+
+    self
+  }
+
+  // normalize paths to unicode normalization form C, templates must be in that form too, panics
+  // if routes were inserted before
+  pub fn set_normalize_unicode(&mut self, value: bool) -> &mut Self {
+    // the templates in the trie were not checked with the old setting
+    assert!(
+      self.leaf_node_indices.is_empty(),
+      "set normalize unicode before inserting routes"
+    );
+    self.normalize_unicode = value;
+
+    self
+  }
+
+  // replace every sequence of slashes in the path with a single slash
+  pub fn set_collapse_duplicate_slashes(&mut self, value: bool) -> &mut Self {
+    self.collapse_duplicate_slashes = value;
+
+    self
+  }
+
+  // resolve `.` and `..` segments in the path, as in RFC 3986
+  pub fn set_remove_dot_segments(&mut self, value: bool) -> &mut Self {
+    self.remove_dot_segments = value;
 
     self
   }
 
+  pub fn set_trailing_slash_policy(&mut self, value: TrailingSlashPolicy) -> &mut Self {
+    self.trailing_slash_policy = value;
+
+    self
+  }
+
+  // the first group is the name of the parameter, without a group it is the whole placeholder
   pub fn set_parameter_placeholder_re(&mut self, value: &'r Regex) -> &mut Self {
     self.parameter_placeholder_re = value;
 
@@ -54,53 +224,495 @@ impl<'r, K: Eq + Hash + Copy> Router<'r, K> {
   }
 
   pub fn set_parameter_value_encoder(&mut self, value: Box<ParameterValueEncoder>) -> &mut Self {
-    self.parameter_value_encoder = value;
+    self.parameter_value_encoder = value.into();
 
     self
   }
 
   pub fn set_parameter_value_decoder(&mut self, value: Box<ParameterValueDecoder>) -> &mut Self {
-    self.parameter_value_decoder = value;
+    self.parameter_value_decoder = value.into();
 
     self
   }
 
   pub fn insert_route(&mut self, route_key: K, template: &'r str) -> &mut Self {
-    let leaf_node_rc = route_node_insert(
-      self.root_node_rc.clone(),
+    self.insert_route_with_priority(route_key, template, 0)
+  }
+
+  // the router is not changed when there is an error
+  pub fn try_insert_route(
+    &mut self,
+    route_key: K,
+    template: &'r str,
+  ) -> Result<&mut Self, InsertRouteError<K>> {
+    self.try_insert_route_with_priority(route_key, template, 0)
+  }
+
+  // when more than one route matches a path, the route with the highest priority wins
+  pub fn insert_route_with_priority(
+    &mut self,
+    route_key: K,
+    template: &'r str,
+    route_priority: i32,
+  ) -> &mut Self {
+    if let Err(error) = self.try_insert_route_with_priority(route_key, template, route_priority) {
+      panic!("{}", error)
+    }
+
+    self
+  }
+
+  pub fn try_insert_route_with_priority(
+    &mut self,
+    route_key: K,
+    template: &'r str,
+    route_priority: i32,
+  ) -> Result<&mut Self, InsertRouteError<K>> {
+    if self.normalize_unicode && !is_nfc(template) {
+      return Err(InsertRouteError::TemplateNotNormalized);
+    }
+
+    let leaf_node_index = route_node_insert(
+      &mut self.nodes,
       route_key,
       template,
+      route_priority,
       self.parameter_placeholder_re,
-    );
-    self.leaf_nodes_rc.insert(route_key, leaf_node_rc);
+      self.ignore_ascii_case,
+    )?;
+    {
+      let leaf_node = &mut self.nodes[leaf_node_index];
+      leaf_node.route_parameter_maximum_value_lengths = leaf_node
+        .route_parameter_names
+        .iter()
+        .map(|parameter_name| {
+          self
+            .parameter_maximum_value_lengths
+            .get(parameter_name)
+            .copied()
+        })
+        .collect();
+    }
+    if self
+      .leaf_node_indices
+      .insert(route_key, leaf_node_index)
+      .is_none()
+    {
+      self.route_keys.push(route_key);
+    }
+
+    Ok(self)
+  }
+
+  // a path that matches the alias parses to the route, parameters are mapped by position
+  pub fn insert_alias(&mut self, template: &'r str, route_key: K) -> &mut Self {
+    if let Err(error) = self.try_insert_alias(template, route_key) {
+      panic!("{}", error)
+    }
+
+    self
+  }
+
+  // the router is not changed when there is an error
+  pub fn try_insert_alias(
+    &mut self,
+    template: &'r str,
+    route_key: K,
+  ) -> Result<&mut Self, InsertRouteError<K>> {
+    if self.normalize_unicode && !is_nfc(template) {
+      return Err(InsertRouteError::TemplateNotNormalized);
+    }
+
+    // an alias has the parameter names, the priority and the parameter limits of the route
+    let (route_parameter_names, route_priority, route_parameter_maximum_value_lengths) =
+      if let Some(&leaf_node_index) = self.leaf_node_indices.get(&route_key) {
+        let leaf_node = &self.nodes[leaf_node_index];
+        (
+          leaf_node.route_parameter_names.clone(),
+          leaf_node.route_priority,
+          leaf_node.route_parameter_maximum_value_lengths.clone(),
+        )
+      } else {
+        return Err(InsertRouteError::UnknownRoute);
+      };
+
+    let alias_parameter_count = parse_template_pairs(template, self.parameter_placeholder_re)
+      .filter(|(_anchor, parameter)| parameter.is_some())
+      .count();
+    if alias_parameter_count != route_parameter_names.len() {
+      return Err(InsertRouteError::AliasParameterCount);
+    }
+
+    let leaf_node_index = route_node_insert_alias(
+      &mut self.nodes,
+      route_key,
+      template,
+      route_priority,
+      route_parameter_names,
+      self.parameter_placeholder_re,
+      self.ignore_ascii_case,
+    )?;
+    self.nodes[leaf_node_index].route_parameter_maximum_value_lengths =
+      route_parameter_maximum_value_lengths;
+
+    Ok(self)
+  }
+
+  // key, template and parameter names of every route in insert order, without the aliases
+  pub fn routes(&self) -> impl Iterator<Item = (K, &'r str, Vec<&'r str>)> + '_ {
+    self.route_keys.iter().map(|route_key| {
+      let leaf_node = &self.nodes[self.leaf_node_indices[route_key]];
+      (
+        *route_key,
+        leaf_node.route_template,
+        leaf_node.route_parameter_names.clone(),
+      )
+    })
+  }
 
+  pub fn template_of(&self, route_key: K) -> Option<&'r str> {
     self
+      .leaf_node_indices
+      .get(&route_key)
+      .map(|&leaf_node_index| self.nodes[leaf_node_index].route_template)
+  }
+
+  pub fn parameter_names_of(&self, route_key: K) -> Option<&[&'r str]> {
+    self
+      .leaf_node_indices
+      .get(&route_key)
+      .map(|&leaf_node_index| self.nodes[leaf_node_index].route_parameter_names.as_slice())
+  }
+
+  pub fn parse_route<'f>(&self, path: &'f str) -> (Option<K>, RouteParameters<'r, 'f>) {
+    self.try_parse_route(path).unwrap_or_default()
+  }
+
+  // a parameter value that cannot be decoded is an error instead of no route
+  pub fn try_parse_route<'f>(
+    &self,
+    path: &'f str,
+  ) -> Result<(Option<K>, RouteParameters<'r, 'f>), ParseRouteError<'r, 'f, K>> {
+    let route_match = self.match_route(path)?;
+
+    if let Some(route_match) = route_match {
+      Ok((Some(route_match.route_key), route_match.route_parameters))
+    } else {
+      Ok(Default::default())
+    }
+  }
+
+  // every route that matches the path, in the order that `parse_route` tries them
+  pub fn parse_route_all<'f>(&self, path: &'f str) -> Vec<(K, RouteParameters<'r, 'f>)> {
+    let mut steps = ParseSteps::new(self.maximum_parse_steps);
+
+    let canonical_path = self.normalize_path(path);
+    let mut routes = self.parse_canonical_path_all(&canonical_path, &mut steps);
+
+    if self.trailing_slash_policy != TrailingSlashPolicy::Strict {
+      if let Some(canonical_path) = toggle_trailing_slash(&canonical_path) {
+        for (route_key, route_parameters) in
+          self.parse_canonical_path_all(&Cow::Owned(canonical_path), &mut steps)
+        {
+          if !routes.iter().any(|(key, _)| *key == route_key) {
+            routes.push((route_key, route_parameters));
+          }
+        }
+      }
+    }
+
+    routes
+  }
+
+  // routers with the same routes have equal tries, whatever the order they were inserted in
+  pub fn trie(&self) -> RouteTrie<'_, 'r, K> {
+    RouteTrie { nodes: &self.nodes }
+  }
+
+  // the match has the canonical path, after normalization and the trailing slash policy
+  pub fn match_route<'f>(
+    &self,
+    path: &'f str,
+  ) -> Result<Option<RouteMatch<'r, 'f, K>>, ParseRouteError<'r, 'f, K>> {
+    let mut steps = ParseSteps::new(self.maximum_parse_steps);
+
+    let canonical_path = self.normalize_path(path);
+
+    // if the path does not parse, the path with the trailing slash toggled may still match
+    let error = match self.parse_canonical_path(&canonical_path, &mut steps) {
+      Ok(Some((route_key, route_parameters, alias))) => {
+        return Ok(Some(RouteMatch {
+          route_key,
+          route_parameters,
+          redirect: self.trailing_slash_policy == TrailingSlashPolicy::Redirect
+            && canonical_path != path,
+          canonical_path,
+          alias,
+        }));
+      }
+      Ok(None) => None,
+      Err(error) => Some(error),
+    };
+
+    let toggled_canonical_path = match self.trailing_slash_policy {
+      TrailingSlashPolicy::Strict => None,
+      _ => toggle_trailing_slash(&canonical_path),
+    };
+    if let Some(canonical_path) = toggled_canonical_path {
+      let canonical_path = Cow::Owned(canonical_path);
+      match self.parse_canonical_path(&canonical_path, &mut steps) {
+        Ok(Some((route_key, route_parameters, alias))) => {
+          return Ok(Some(RouteMatch {
+            route_key,
+            route_parameters,
+            redirect: self.trailing_slash_policy == TrailingSlashPolicy::Redirect,
+            canonical_path,
+            alias,
+          }));
+        }
+        Ok(None) => {}
+        Err(toggled_error) => return Err(error.unwrap_or(toggled_error)),
+      }
+    }
+
+    match error {
+      Some(error) => Err(error),
+      None => Ok(None),
+    }
+  }
+
+  // every node, anchor and child that is tried when matching the path
+  pub fn explain_parse(&self, path: &str) -> ParseTrace<'r, K> {
+    let mut trace = ParseTrace::default();
+    let mut steps = ParseSteps::new(self.maximum_parse_steps);
+
+    let canonical_path = self.normalize_path(path);
+    let mut canonical_paths = vec![canonical_path.clone()];
+    if self.trailing_slash_policy != TrailingSlashPolicy::Strict {
+      if let Some(canonical_path) = toggle_trailing_slash(&canonical_path) {
+        canonical_paths.push(Cow::Owned(canonical_path));
+      }
+    }
+
+    for canonical_path in canonical_paths {
+      trace.start(&canonical_path);
+
+      if let Some(leaf_node_index) = route_node_parse(
+        &self.nodes,
+        ROOT_NODE_INDEX,
+        &canonical_path,
+        self.maximum_parameter_value_length,
+        true,
+        self.ignore_ascii_case,
+        &mut steps,
+        &mut Vec::new(),
+        Some(&mut trace),
+      ) {
+        trace.route_key = self.nodes[leaf_node_index].route_key;
+        break;
+      }
+    }
+
+    trace
+  }
+
+  fn normalize_path<'f>(&self, path: &'f str) -> Cow<'f, str> {
+    let mut path = Cow::Borrowed(path);
+
+    if self.normalize_unicode {
+      if let Cow::Owned(normalized_path) = normalize_unicode(&path) {
+        path = Cow::Owned(normalized_path);
+      }
+    }
+
+    if self.collapse_duplicate_slashes {
+      if let Some(normalized_path) = collapse_duplicate_slashes(&path) {
+        path = Cow::Owned(normalized_path);
+      }
+    }
+
+    if self.remove_dot_segments {
+      if let Some(normalized_path) = remove_dot_segments(&path) {
+        path = Cow::Owned(normalized_path);
+      }
+    }
+
+    path
+  }
+
+  fn parse_canonical_path<'f>(
+    &self,
+    path: &Cow<'f, str>,
+    steps: &mut ParseSteps,
+  ) -> Result<Option<(K, RouteParameters<'r, 'f>, bool)>, ParseRouteError<'r, 'f, K>> {
+    match path {
+      Cow::Borrowed(path) => self.parse_path(path, steps),
+      Cow::Owned(path) => match self.parse_path(path, steps) {
+        Ok(result) => Ok(result.map(|(route_key, route_parameters, alias)| {
+          (route_key, into_owned_parameters(route_parameters), alias)
+        })),
+        Err(error) => Err(error.into_owned()),
+      },
+    }
+  }
+
+  fn parse_canonical_path_all<'f>(
+    &self,
+    path: &Cow<'f, str>,
+    steps: &mut ParseSteps,
+  ) -> Vec<(K, RouteParameters<'r, 'f>)> {
+    match path {
+      Cow::Borrowed(path) => self.parse_path_all(path, steps),
+      Cow::Owned(path) => self
+        .parse_path_all(path, steps)
+        .into_iter()
+        .map(|(route_key, route_parameters)| (route_key, into_owned_parameters(route_parameters)))
+        .collect(),
+    }
   }
 
-  pub fn parse_route<'f>(&self, path: &'f str) -> (Option<K>, HashMap<&'r str, Cow<'f, str>>) {
-    let (route_key, parameter_names, parameter_values) = route_node_parse(
-      self.root_node_rc.clone(),
+  fn parse_path<'f>(
+    &self,
+    path: &'f str,
+    steps: &mut ParseSteps,
+  ) -> Result<Option<(K, RouteParameters<'r, 'f>, bool)>, ParseRouteError<'r, 'f, K>> {
+    let mut parameter_values = Vec::new();
+    if let Some(leaf_node_index) = route_node_parse(
+      &self.nodes,
+      ROOT_NODE_INDEX,
       path,
       self.maximum_parameter_value_length,
-    );
+      true,
+      self.ignore_ascii_case,
+      steps,
+      &mut parameter_values,
+      None,
+    ) {
+      return self
+        .decode_parameters(leaf_node_index, parameter_values)
+        .map(Some);
+    }
+
+    if let (true, Some(maximum_steps)) = (steps.exhausted, steps.maximum) {
+      return Err(ParseRouteError::TooManySteps { maximum_steps });
+    }
+
+    if self.maximum_parameter_value_length.is_none()
+      && self.parameter_maximum_value_lengths.is_empty()
+    {
+      return Ok(None);
+    }
+
+    // find out if the path did not match because of a limit, by parsing again without limits
+    if let Some(leaf_node_index) = route_node_parse(
+      &self.nodes,
+      ROOT_NODE_INDEX,
+      path,
+      None,
+      false,
+      self.ignore_ascii_case,
+      steps,
+      &mut parameter_values,
+      None,
+    ) {
+      let leaf_node = &self.nodes[leaf_node_index];
+      let route_key = leaf_node.route_key.expect("leaf node without route key");
 
-    if let Some(route_key) = route_key {
-      let parameters: HashMap<_, _> = parameter_names
+      for ((parameter_name, route_parameter_maximum_value_length), parameter_value) in leaf_node
+        .route_parameter_names
         .iter()
         .cloned()
-        .zip(
-          parameter_values
-            .iter()
-            .map(|parameter_value| (self.parameter_value_decoder)(parameter_value)),
-        )
-        .collect();
+        .zip(leaf_node.route_parameter_maximum_value_lengths.iter())
+        .zip(parameter_values)
+      {
+        let maximum_length = match (
+          self.maximum_parameter_value_length,
+          route_parameter_maximum_value_length,
+        ) {
+          (Some(left), Some(right)) => Some(left.min(*right)),
+          (left, right) => left.or(*right),
+        };
 
-      (Some(route_key), parameters)
-    } else {
-      Default::default()
+        if let Some(maximum_length) = maximum_length {
+          if parameter_value.len() > maximum_length {
+            return Err(ParseRouteError::ParameterValueTooLong {
+              route_key,
+              parameter_name,
+              parameter_value: Cow::Borrowed(parameter_value),
+              maximum_length,
+            });
+          }
+        }
+      }
+    }
+
+    if let (true, Some(maximum_steps)) = (steps.exhausted, steps.maximum) {
+      return Err(ParseRouteError::TooManySteps { maximum_steps });
+    }
+
+    Ok(None)
+  }
+
+  fn parse_path_all<'f>(
+    &self,
+    path: &'f str,
+    steps: &mut ParseSteps,
+  ) -> Vec<(K, RouteParameters<'r, 'f>)> {
+    let mut matches = Vec::new();
+    route_node_parse_all(
+      &self.nodes,
+      ROOT_NODE_INDEX,
+      path,
+      self.maximum_parameter_value_length,
+      self.ignore_ascii_case,
+      steps,
+      &mut Vec::new(),
+      &mut matches,
+    );
+
+    matches
+      .into_iter()
+      .filter_map(|(leaf_node_index, parameter_values)| {
+        self
+          .decode_parameters(leaf_node_index, parameter_values)
+          .ok()
+          .map(|(route_key, route_parameters, _alias)| (route_key, route_parameters))
+      })
+      .collect()
+  }
+
+  fn decode_parameters<'f>(
+    &self,
+    leaf_node_index: usize,
+    parameter_values: Vec<&'f str>,
+  ) -> Result<(K, RouteParameters<'r, 'f>, bool), ParseRouteError<'r, 'f, K>> {
+    let leaf_node = &self.nodes[leaf_node_index];
+    let route_key = leaf_node.route_key.expect("leaf node without route key");
+
+    let mut parameters = HashMap::new();
+
+    for (parameter_name, parameter_value) in leaf_node
+      .route_parameter_names
+      .iter()
+      .cloned()
+      .zip(parameter_values)
+    {
+      let parameter_value = (self.parameter_value_decoder)(parameter_value).map_err(|source| {
+        ParseRouteError::ParameterValueDecode {
+          route_key,
+          parameter_name,
+          parameter_value: Cow::Borrowed(parameter_value),
+          source,
+        }
+      })?;
+
+      parameters.insert(parameter_name, parameter_value);
     }
+
+    Ok((route_key, parameters, leaf_node.route_alias))
   }
 
+  // None if there is no route with the key, or if a parameter is missing
   pub fn stringify_route<'f>(
     &self,
     route_key: K,
@@ -109,22 +721,105 @@ impl<'r, K: Eq + Hash + Copy> Router<'r, K> {
   where
     'r: 'f,
   {
-    if let Some(node_rc) = self.leaf_nodes_rc.get(&route_key) {
-      let parameter_values: Vec<_> = node_rc
-        .borrow()
-        .route_parameter_names
-        .iter()
-        .map(|parameter_name| route_parameters.get(parameter_name).unwrap())
-        .map(|parameter_value| (self.parameter_value_encoder)(parameter_value))
-        .collect();
+    let leaf_node_index = *self.leaf_node_indices.get(&route_key)?;
+    let parameter_values = self.nodes[leaf_node_index]
+      .route_parameter_names
+      .iter()
+      .map(|parameter_name| route_parameters.get(parameter_name))
+      .map(|parameter_value| parameter_value.map(|value| (self.parameter_value_encoder)(value)))
+      .collect::<Option<Vec<_>>>()?;
 
-      Some(route_node_stringify(node_rc.clone(), parameter_values))
-    } else {
-      None
+    Some(route_node_stringify(
+      &self.nodes,
+      leaf_node_index,
+      parameter_values,
+    ))
+  }
+
+  // the path is parsed again to check that it round trips to the same route and parameters
+  pub fn try_stringify_route<'f>(
+    &self,
+    route_key: K,
+    route_parameters: &'f HashMap<&'f str, &'f str>,
+  ) -> Result<Cow<'f, str>, StringifyRouteError<'r, K>>
+  where
+    'r: 'f,
+  {
+    let leaf_node_index = *self
+      .leaf_node_indices
+      .get(&route_key)
+      .ok_or(StringifyRouteError::UnknownRoute)?;
+    let route_parameter_names = &self.nodes[leaf_node_index].route_parameter_names;
+    if let Some(parameter_name) = route_parameter_names
+      .iter()
+      .find(|parameter_name| !route_parameters.contains_key(*parameter_name))
+    {
+      return Err(StringifyRouteError::MissingParameter { parameter_name });
     }
+
+    let path = self
+      .stringify_route(route_key, route_parameters)
+      .ok_or(StringifyRouteError::UnknownRoute)?;
+
+    let (parsed_route_key, parsed_route_parameters) = self.parse_route(&path);
+    let round_trips = parsed_route_key == Some(route_key)
+      && route_parameter_names.iter().all(|parameter_name| {
+        parsed_route_parameters
+          .get(parameter_name)
+          .map(|parameter_value| parameter_value.as_ref())
+          == route_parameters.get(parameter_name).copied()
+      });
+    if !round_trips {
+      return Err(StringifyRouteError::NotRepresentable {
+        path: path.into_owned(),
+        parsed_route_key,
+      });
+    }
+
+    Ok(path)
+  }
+
+  // the path of the route of a match, also when the match is an alias
+  pub fn stringify_route_match(&self, route_match: &RouteMatch<'r, '_, K>) -> Option<String> {
+    let route_parameters: HashMap<_, _> = route_match
+      .route_parameters
+      .iter()
+      .map(|(name, value)| (*name, value.as_ref()))
+      .collect();
+
+    self
+      .stringify_route(route_match.route_key, &route_parameters)
+      .map(Cow::into_owned)
+  }
+}
+
+impl<'r, K: Eq + Hash + Copy + Debug> Router<'r, K> {
+  // the trie as indented text, a line per node
+  pub fn dump_tree(&self) -> String {
+    let mut output = String::new();
+    route_node_dump_tree(&self.nodes, ROOT_NODE_INDEX, 0, &mut output);
+    output
+  }
+
+  // the trie as a graphviz digraph
+  pub fn to_dot(&self) -> String {
+    let mut output = String::new();
+    output.push_str("digraph {\n");
+    route_node_dump_dot(&self.nodes, ROOT_NODE_INDEX, &mut 0, &mut output);
+    output.push_str("}\n");
+    output
   }
 }
 
+fn into_owned_parameters<'r>(
+  route_parameters: RouteParameters<'r, '_>,
+) -> RouteParameters<'r, 'static> {
+  route_parameters
+    .into_iter()
+    .map(|(name, value)| (name, Cow::Owned(value.into_owned())))
+    .collect()
+}
+
 impl<'r, K: Eq + Hash + Copy> Default for Router<'r, K> {
   fn default() -> Self {
     Self::new()
@@ -134,7 +829,13 @@ impl<'r, K: Eq + Hash + Copy> Default for Router<'r, K> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::parse_trace::ParseTraceEvent;
+  use crate::template::read_template_lines;
+  use proptest::prelude::*;
+  use proptest::test_runner::TestRunner;
+  use serde::Deserialize;
   use std::collections::HashSet;
+  use std::thread;
 
   #[test]
   fn readme() {
@@ -308,35 +1009,1301 @@ mod tests {
   }
 
   #[test]
-  fn router_templates_small() {
-    router_templates("small")
-  }
+  fn router_decode_error() {
+    let mut router = Router::new();
 
-  #[test]
-  fn router_templates_docker() {
-    router_templates("docker")
-  }
+    router.insert_route("product-detail", "/product/{id}");
 
-  #[test]
-  fn router_templates_github() {
-    router_templates("github")
-  }
+    {
+      let (route_key, route_parameters) = router.try_parse_route("/product/%31").unwrap();
+      assert_eq!(route_key, Some("product-detail"));
+      assert_eq!(route_parameters["id"], "1");
+    }
 
-  fn router_templates(name: &str) {
-    let mut path = std::path::PathBuf::new();
-    path.push("..");
-    path.push("..");
-    path.push("..");
-    path.push("fixtures");
-    path.push(name);
-    path.set_extension("txt");
+    {
+      let (route_key, route_parameters) = router.try_parse_route("/not-found").unwrap();
+      assert_eq!(route_key, None);
+      assert_eq!(route_parameters, Default::default());
+    }
 
-    let templates = std::fs::read_to_string(path.as_path()).unwrap();
-    let templates: Vec<_> = templates
-      .split('\n')
-      .map(|line| line.trim())
-      .filter(|line| !line.is_empty())
-      .collect();
+    {
+      let error = router.try_parse_route("/product/%FF").unwrap_err();
+      let ParseRouteError::ParameterValueDecode {
+        route_key,
+        parameter_name,
+        parameter_value,
+        ..
+      } = error
+      else {
+        panic!("expected a decode error");
+      };
+      assert_eq!(route_key, "product-detail");
+      assert_eq!(parameter_name, "id");
+      assert_eq!(parameter_value, "%FF");
+    }
+
+    {
+      let (route_key, route_parameters) = router.parse_route("/product/%FF");
+      assert_eq!(route_key, None);
+      assert_eq!(route_parameters, Default::default());
+    }
+
+    router.set_parameter_value_decoder(Box::new(|value| {
+      if value.is_empty() || value.bytes().all(|byte| byte.is_ascii_digit()) {
+        Ok(Cow::Borrowed(value))
+      } else {
+        Err(format!("{} is not a number", value).into())
+      }
+    }));
+
+    {
+      let (route_key, _route_parameters) = router.try_parse_route("/product/12").unwrap();
+      assert_eq!(route_key, Some("product-detail"));
+    }
+
+    {
+      let error = router.try_parse_route("/product/twelve").unwrap_err();
+      assert_eq!(
+        error.to_string(),
+        "could not decode value \"twelve\" for parameter id: twelve is not a number"
+      );
+    }
+  }
+
+  #[test]
+  fn router_ignore_ascii_case() {
+    let mut router = Router::new();
+
+    router
+      .set_ignore_ascii_case(true)
+      .insert_route("all-products", "/product/all")
+      .insert_route("product-detail", "/PRODUCT/{id}/Detail");
+
+    let (route_key, _route_parameters) = router.parse_route("/Product/ALL");
+    assert_eq!(route_key, Some("all-products"));
+
+    let (route_key, route_parameters) = router.parse_route("/product/Ab/DETAIL");
+    assert_eq!(route_key, Some("product-detail"));
+    assert_eq!(route_parameters["id"], "Ab");
+
+    let route_parameters = vec![("id", "Ab")].into_iter().collect();
+    let path = router.stringify_route("product-detail", &route_parameters);
+    assert_eq!(path.unwrap(), "/product/Ab/Detail");
+  }
+
+  #[test]
+  #[should_panic(expected = "ambiguous route")]
+  fn router_ignore_ascii_case_ambiguous() {
+    let mut router = Router::new();
+
+    router
+      .set_ignore_ascii_case(true)
+      .insert_route("a", "/a/{x}/b")
+      .insert_route("b", "/A/{y}/B");
+  }
+
+  #[test]
+  fn router_normalize_unicode() {
+    let mut router = Router::new();
+
+    router.insert_route("cafe", "/caf\u{e9}/{id}");
+
+    let (route_key, _route_parameters) = router.parse_route("/cafe\u{301}/1");
+    assert_eq!(route_key, None);
+
+    let mut router = Router::new();
+
+    router
+      .set_normalize_unicode(true)
+      .insert_route("cafe", "/caf\u{e9}/{id}");
+
+    let (route_key, route_parameters) = router.parse_route("/cafe\u{301}/1");
+    assert_eq!(route_key, Some("cafe"));
+    assert_eq!(route_parameters["id"], "1");
+  }
+
+  #[test]
+  #[should_panic(expected = "set ignore ascii case before inserting routes")]
+  fn router_ignore_ascii_case_after_insert() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("a", "/a/{x}")
+      .set_ignore_ascii_case(true);
+  }
+
+  #[test]
+  #[should_panic(expected = "set normalize unicode before inserting routes")]
+  fn router_normalize_unicode_after_insert() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("cafe", "/cafe\u{301}/{id}")
+      .set_normalize_unicode(true);
+  }
+
+  #[test]
+  fn router_multi_byte_anchors() {
+    let mut router = Router::new();
+
+    router
+      .set_maximum_parameter_value_length(2)
+      .insert_route("a", "/\u{e9}\u{e9}/a/{x}/y")
+      .insert_route("b", "/\u{e9}\u{e9}/b");
+
+    let (route_key, _route_parameters) = router.parse_route("/\u{e9}\u{e9}/b");
+    assert_eq!(route_key, Some("b"));
+
+    let (route_key, route_parameters) = router.parse_route("/\u{e9}\u{e9}/a/\u{e9}/y");
+    assert_eq!(route_key, Some("a"));
+    assert_eq!(route_parameters["x"], "\u{e9}");
+
+    let (route_key, _route_parameters) = router.parse_route("/\u{e9}\u{e9}/a/a\u{e9}\u{e9}/y");
+    assert_eq!(route_key, None);
+  }
+
+  #[test]
+  fn router_path_normalization() {
+    let mut router = Router::new();
+
+    router.insert_route("product-detail", "/product/{id}");
+
+    let (route_key, _route_parameters) = router.parse_route("//product/./1");
+    assert_eq!(route_key, None);
+
+    router
+      .set_collapse_duplicate_slashes(true)
+      .set_remove_dot_segments(true);
+
+    let (route_key, route_parameters) = router.parse_route("//product/./1");
+    assert_eq!(route_key, Some("product-detail"));
+    assert_eq!(route_parameters["id"], "1");
+
+    let route_match = router.match_route("/product/x/../2").unwrap().unwrap();
+    assert_eq!(route_match.route_key, "product-detail");
+    assert_eq!(route_match.route_parameters["id"], "2");
+    assert_eq!(route_match.canonical_path, "/product/2");
+    assert!(!route_match.redirect);
+  }
+
+  #[test]
+  fn router_trailing_slash_policy() {
+    let mut router = Router::new();
+
+    router
+      .set_collapse_duplicate_slashes(true)
+      .set_remove_dot_segments(true)
+      .insert_route("product-detail", "/product/{id}/detail")
+      .insert_route("product-list", "/product/");
+
+    assert!(router.match_route("/product/1/detail/").unwrap().is_none());
+    assert!(router.match_route("/product").unwrap().is_none());
+
+    router.set_trailing_slash_policy(TrailingSlashPolicy::Ignore);
+
+    let route_match = router.match_route("/product/1/detail/").unwrap().unwrap();
+    assert_eq!(route_match.route_key, "product-detail");
+    assert_eq!(route_match.route_parameters["id"], "1");
+    assert_eq!(route_match.canonical_path, "/product/1/detail");
+    assert!(!route_match.redirect);
+
+    let route_match = router.match_route("/product").unwrap().unwrap();
+    assert_eq!(route_match.route_key, "product-list");
+    assert_eq!(route_match.canonical_path, "/product/");
+    assert!(!route_match.redirect);
+
+    router.set_trailing_slash_policy(TrailingSlashPolicy::Redirect);
+
+    let route_match = router
+      .match_route("//product/./1/detail/")
+      .unwrap()
+      .unwrap();
+    assert_eq!(route_match.route_key, "product-detail");
+    assert_eq!(route_match.canonical_path, "/product/1/detail");
+    assert!(route_match.redirect);
+
+    let route_match = router.match_route("//product/./1/detail").unwrap().unwrap();
+    assert_eq!(route_match.canonical_path, "/product/1/detail");
+    assert!(route_match.redirect);
+
+    let route_match = router.match_route("/product/1/detail").unwrap().unwrap();
+    assert_eq!(route_match.canonical_path, "/product/1/detail");
+    assert!(!route_match.redirect);
+  }
+
+  #[test]
+  fn router_trailing_slash_policy_error() {
+    let mut router = Router::new();
+
+    router
+      .set_trailing_slash_policy(TrailingSlashPolicy::Ignore)
+      .set_maximum_parameter_value_length(3)
+      .insert_route("a", "/a/{x}/")
+      .insert_route("b", "/b/{x}");
+
+    // neither path matches, the error of the first path is returned
+    assert!(matches!(
+      router.match_route("/a/%FF/"),
+      Err(ParseRouteError::ParameterValueDecode { route_key: "a", .. })
+    ));
+
+    // the value is too long with the trailing slash, but the path without it matches
+    let route_match = router.match_route("/b/abc/").unwrap().unwrap();
+    assert_eq!(route_match.route_key, "b");
+    assert_eq!(route_match.route_parameters["x"], "abc");
+    assert_eq!(route_match.canonical_path, "/b/abc");
+
+    router.set_trailing_slash_policy(TrailingSlashPolicy::Strict);
+    assert!(matches!(
+      router.match_route("/b/abc/"),
+      Err(ParseRouteError::ParameterValueTooLong { route_key: "b", .. })
+    ));
+  }
+
+  #[test]
+  fn router_alias() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("product-detail", "/product/{id}/{tab}")
+      .insert_alias("/products/{product-id}/{tab-name}", "product-detail");
+
+    {
+      let route_match = router.match_route("/product/1/specs").unwrap().unwrap();
+      assert_eq!(route_match.route_key, "product-detail");
+      assert!(!route_match.alias);
+    }
+
+    {
+      let route_match = router.match_route("/products/1/specs").unwrap().unwrap();
+      assert_eq!(route_match.route_key, "product-detail");
+      assert_eq!(route_match.route_parameters["id"], "1");
+      assert_eq!(route_match.route_parameters["tab"], "specs");
+      assert!(route_match.alias);
+
+      let path = router.stringify_route_match(&route_match).unwrap();
+      assert_eq!(path, "/product/1/specs");
+    }
+
+    {
+      let (route_key, route_parameters) = router.parse_route("/products/2/reviews");
+      assert_eq!(route_key, Some("product-detail"));
+      assert_eq!(
+        route_parameters,
+        vec![("id", "2"), ("tab", "reviews")]
+          .into_iter()
+          .map(|(k, v)| (k, Cow::Borrowed(v)))
+          .collect()
+      );
+    }
+
+    // the alias does not change how the route is stringified
+    {
+      let route_parameters = vec![("id", "3"), ("tab", "specs")].into_iter().collect();
+      let path = router.stringify_route("product-detail", &route_parameters);
+      assert_eq!(path.unwrap(), "/product/3/specs");
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "alias parameters do not match route parameters")]
+  fn router_alias_parameters() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("product-detail", "/product/{id}/{tab}")
+      .insert_alias("/p/{id}", "product-detail");
+  }
+
+  #[test]
+  fn router_clone_send_sync() {
+    let mut router = Router::new();
+    router.insert_route("product-detail", "/product/{id}");
+
+    // a clone does not share its routes with the original
+    let mut router_clone = router.clone();
+    router_clone.insert_route("all-products", "/product/all");
+    assert_eq!(router.parse_route("/product/all").0, Some("product-detail"));
+    assert_eq!(
+      router_clone.parse_route("/product/all").0,
+      Some("all-products")
+    );
+
+    let route_key = thread::scope(|scope| {
+      scope
+        .spawn(|| router.parse_route("/product/1").0)
+        .join()
+        .unwrap()
+    });
+    assert_eq!(route_key, Some("product-detail"));
+  }
+
+  #[test]
+  fn router_stringify_missing_parameter() {
+    let mut router = Router::new();
+
+    router.insert_route("product-detail", "/product/{id}/{tab}");
+
+    let route_parameters = HashMap::from([("id", "1")]);
+    assert_eq!(
+      router.stringify_route("product-detail", &route_parameters),
+      None
+    );
+    assert_eq!(router.stringify_route("unknown", &route_parameters), None);
+
+    let route_parameters = HashMap::from([("id", "1"), ("tab", "specs")]);
+    assert_eq!(
+      router
+        .stringify_route("product-detail", &route_parameters)
+        .unwrap(),
+      "/product/1/specs"
+    );
+  }
+
+  #[test]
+  fn router_try_stringify_route() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("product-detail", "/product/{id}")
+      .insert_route("all-products", "/product/all")
+      .insert_route("range", "/range/{from}-{to}");
+
+    let route_parameters = HashMap::from([("id", "a/b%"), ("from", "1"), ("to", "2")]);
+    assert_eq!(
+      router
+        .try_stringify_route("product-detail", &route_parameters)
+        .unwrap(),
+      "/product/a%2Fb%25"
+    );
+    assert_eq!(
+      router
+        .try_stringify_route("range", &route_parameters)
+        .unwrap(),
+      "/range/1-2"
+    );
+    assert_eq!(
+      router.try_stringify_route("unknown", &route_parameters),
+      Err(StringifyRouteError::UnknownRoute)
+    );
+
+    let route_parameters = HashMap::from([("from", "1")]);
+    assert_eq!(
+      router.try_stringify_route("range", &route_parameters),
+      Err(StringifyRouteError::MissingParameter {
+        parameter_name: "to"
+      })
+    );
+
+    // the path matches another route
+    let route_parameters = HashMap::from([("id", "all")]);
+    assert_eq!(
+      router.try_stringify_route("product-detail", &route_parameters),
+      Err(StringifyRouteError::NotRepresentable {
+        path: "/product/all".to_owned(),
+        parsed_route_key: Some("all-products"),
+      })
+    );
+
+    // the value contains the anchor that follows it
+    let route_parameters = HashMap::from([("from", "-1"), ("to", "2")]);
+    let error = router
+      .try_stringify_route("range", &route_parameters)
+      .unwrap_err();
+    assert_eq!(
+      error,
+      StringifyRouteError::NotRepresentable {
+        path: "/range/-1-2".to_owned(),
+        parsed_route_key: Some("range"),
+      }
+    );
+    assert_eq!(
+      error.to_string(),
+      "path \"/range/-1-2\" does not parse to the route with the same parameters"
+    );
+
+    // an empty value at the end of the path does not match
+    let route_parameters = HashMap::from([("id", "")]);
+    assert_eq!(
+      router.try_stringify_route("product-detail", &route_parameters),
+      Err(StringifyRouteError::NotRepresentable {
+        path: "/product/".to_owned(),
+        parsed_route_key: None,
+      })
+    );
+  }
+
+  #[test]
+  fn router_round_trip_template() {
+    // values that are made of anchors, or of characters that are encoded
+    let value_strategy = prop_oneof![any::<String>(), "[a/.%-]{0,4}"];
+    let part_strategy = prop_oneof!["[a-c/.%-]{0,3}".prop_map(Some), Just(None)];
+    let strategy = (
+      prop::collection::vec(part_strategy, 1..6),
+      prop::collection::vec(value_strategy, 6),
+    );
+
+    round_trip_runner()
+      .run(&strategy, |(parts, values)| {
+        // a part is a literal, or a placeholder
+        let template: String = parts
+          .iter()
+          .enumerate()
+          .map(|(index, part)| match part {
+            Some(literal) => literal.clone(),
+            None => format!("{{p{}}}", index),
+          })
+          .collect();
+        let parameter_names: Vec<_> = (0..parts.len())
+          .map(|index| format!("p{}", index))
+          .collect();
+        let route_parameters: HashMap<_, _> = parameter_names
+          .iter()
+          .map(|name| name.as_str())
+          .zip(values.iter().map(|value| value.as_str()))
+          .collect();
+
+        let mut router = Router::new();
+        router.insert_route("route", &template);
+
+        // a value ends at the first occurrence of the anchor after it, and the value of a
+        // parameter without an anchor is the rest of the path, that may not be empty
+        let mut representable = true;
+        for (index, part) in parts.iter().enumerate() {
+          if part.is_some() {
+            continue;
+          }
+          let anchor: String = parts[index + 1..]
+            .iter()
+            .map_while(|part| part.as_deref())
+            .collect();
+          let value = urlencoding::encode(route_parameters[parameter_names[index].as_str()]);
+
+          representable &= if anchor.is_empty() {
+            parts[index + 1..].iter().all(Option::is_some) && !value.is_empty()
+          } else {
+            (value.clone() + anchor.as_str()).find(&anchor) == Some(value.len())
+          };
+        }
+
+        let result = router.try_stringify_route("route", &route_parameters);
+        prop_assert_eq!(result.is_ok(), representable, "{} {:?}", template, result);
+
+        if let Ok(path) = result {
+          let (route_key, parsed_route_parameters) = router.parse_route(&path);
+          prop_assert_eq!(route_key, Some("route"));
+          for parameter_name in router.routes().flat_map(|(.., names)| names) {
+            prop_assert_eq!(
+              &parsed_route_parameters[parameter_name],
+              route_parameters[parameter_name]
+            );
+          }
+        }
+
+        Ok(())
+      })
+      .unwrap();
+  }
+
+  #[test]
+  fn router_round_trip_github() {
+    let templates = read_fixture("github");
+    let templates = read_template_lines(&templates);
+
+    let mut router = Router::new();
+    for template in templates.iter().cloned() {
+      router.insert_route(template, template);
+    }
+
+    let value_strategy = prop_oneof![any::<String>(), "[a-z0-9/.%-]{0,8}"];
+    let strategy = (0..templates.len(), prop::collection::vec(value_strategy, 8));
+
+    // if the path of a route can be stringified, it parses to the same route and values
+    round_trip_runner()
+      .run(&strategy, |(index, values)| {
+        let template = templates[index];
+        let parameter_names: Vec<_> = router
+          .routes()
+          .find(|(route_key, ..)| *route_key == template)
+          .map(|(.., parameter_names)| parameter_names)
+          .unwrap();
+        let route_parameters: HashMap<_, _> = parameter_names
+          .iter()
+          .cloned()
+          .zip(values.iter().map(|value| value.as_str()))
+          .collect();
+
+        if let Ok(path) = router.try_stringify_route(template, &route_parameters) {
+          let (route_key, parsed_route_parameters) = router.parse_route(&path);
+          prop_assert_eq!(route_key, Some(template));
+          for parameter_name in parameter_names {
+            prop_assert_eq!(
+              &parsed_route_parameters[parameter_name],
+              route_parameters[parameter_name]
+            );
+          }
+        }
+
+        Ok(())
+      })
+      .unwrap();
+  }
+
+  #[test]
+  fn router_trie() {
+    fn hash(trie: RouteTrie<'_, '_, &str>) -> u64 {
+      let mut hasher = std::collections::hash_map::DefaultHasher::new();
+      trie.hash(&mut hasher);
+      hasher.finish()
+    }
+
+    let mut router = Router::new();
+    router
+      .insert_route("a", "/a")
+      .insert_route("b", "/b/{x}")
+      .insert_route("c", "/b/{x}/c");
+
+    let mut other_router = Router::new();
+    other_router
+      .insert_route("c", "/b/{x}/c")
+      .insert_route("a", "/a")
+      .insert_route("b", "/b/{x}");
+
+    assert_eq!(router.trie(), other_router.trie());
+    assert_eq!(hash(router.trie()), hash(other_router.trie()));
+
+    // the key of a route is part of the trie
+    let mut other_router = Router::new();
+    other_router
+      .insert_route("a", "/a")
+      .insert_route("b", "/b/{x}")
+      .insert_route("d", "/b/{x}/c");
+    assert_ne!(router.trie(), other_router.trie());
+    assert_ne!(hash(router.trie()), hash(other_router.trie()));
+
+    // and so is the priority
+    let mut other_router = Router::new();
+    other_router
+      .insert_route("a", "/a")
+      .insert_route_with_priority("b", "/b/{x}", 1)
+      .insert_route("c", "/b/{x}/c");
+    assert_ne!(router.trie(), other_router.trie());
+
+    // and the aliases
+    let mut other_router = router.clone();
+    assert_eq!(router.trie(), other_router.trie());
+    other_router.insert_alias("/c/{x}", "b");
+    assert_ne!(router.trie(), other_router.trie());
+  }
+
+  #[test]
+  fn router_trie_shuffled_docker() {
+    router_trie_shuffled("docker")
+  }
+
+  #[test]
+  fn router_trie_shuffled_github() {
+    router_trie_shuffled("github")
+  }
+
+  // insert the templates of a fixture in any order, the trie and the parse results are the same
+  fn router_trie_shuffled(name: &str) {
+    let templates = read_fixture(name);
+    let templates = read_template_lines(&templates);
+
+    let mut router = Router::new();
+    for template in templates.iter().cloned() {
+      router.insert_route(template, template);
+    }
+
+    let all_parameters: HashMap<_, _> = router
+      .routes()
+      .flat_map(|(.., parameter_names)| parameter_names)
+      .map(|parameter_name| (parameter_name, parameter_name))
+      .collect();
+    let paths: Vec<_> = templates
+      .iter()
+      .map(|template| router.stringify_route(template, &all_parameters).unwrap())
+      .collect();
+
+    let indices: Vec<_> = (0..templates.len()).collect();
+    let mut runner = TestRunner::new(ProptestConfig {
+      cases: 16,
+      failure_persistence: None,
+      ..ProptestConfig::default()
+    });
+    runner
+      .run(&Just(indices).prop_shuffle(), |indices| {
+        let mut shuffled_router = Router::new();
+        for index in indices {
+          shuffled_router.insert_route(templates[index], templates[index]);
+        }
+
+        prop_assert_eq!(shuffled_router.trie(), router.trie());
+        for path in paths.iter() {
+          prop_assert_eq!(shuffled_router.parse_route(path), router.parse_route(path));
+          prop_assert_eq!(
+            shuffled_router.parse_route_all(path),
+            router.parse_route_all(path)
+          );
+        }
+
+        Ok(())
+      })
+      .unwrap();
+  }
+
+  #[test]
+  fn router_routes() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("product-detail", "/product/{id}")
+      .insert_route("all-products", "/product/all")
+      .insert_route("product-tab", "/product/{id}/{tab}")
+      .insert_alias("/products/{product-id}", "product-detail");
+
+    let routes: Vec<_> = router.routes().collect();
+    assert_eq!(
+      routes,
+      vec![
+        ("product-detail", "/product/{id}", vec!["id"]),
+        ("all-products", "/product/all", vec![]),
+        ("product-tab", "/product/{id}/{tab}", vec!["id", "tab"]),
+      ]
+    );
+
+    assert_eq!(
+      router.template_of("product-tab"),
+      Some("/product/{id}/{tab}")
+    );
+    assert_eq!(router.template_of("not-found"), None);
+
+    assert_eq!(
+      router.parameter_names_of("product-tab"),
+      Some(["id", "tab"].as_slice())
+    );
+    assert_eq!(router.parameter_names_of("not-found"), None);
+  }
+
+  #[test]
+  fn router_parse_route_all() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("all-products", "/product/all")
+      .insert_route("product-detail", "/product/{id}")
+      .insert_route("category-all", "/{category}/all")
+      .insert_route("page", "/{page}");
+
+    let routes: Vec<_> = router
+      .parse_route_all("/product/all")
+      .into_iter()
+      .map(|(route_key, route_parameters)| {
+        let mut route_parameters: Vec<_> = route_parameters
+          .into_iter()
+          .map(|(name, value)| (name, value.into_owned()))
+          .collect();
+        route_parameters.sort();
+        (route_key, route_parameters)
+      })
+      .collect();
+    assert_eq!(
+      routes,
+      vec![
+        ("all-products", vec![]),
+        ("product-detail", vec![("id", "all".to_owned())]),
+        ("category-all", vec![("category", "product".to_owned())]),
+        ("page", vec![("page", "product/all".to_owned())]),
+      ]
+    );
+
+    let (route_key, _route_parameters) = router.parse_route("/product/all");
+    assert_eq!(route_key, Some(routes[0].0));
+
+    let route_keys: Vec<_> = router
+      .parse_route_all("/product/1")
+      .into_iter()
+      .map(|(route_key, _route_parameters)| route_key)
+      .collect();
+    assert_eq!(route_keys, vec!["product-detail", "page"]);
+
+    assert!(router.parse_route_all("").is_empty());
+  }
+
+  #[test]
+  fn router_route_priority() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("all-products", "/product/all")
+      .insert_route_with_priority("product-detail", "/product/{id}", 1)
+      .insert_alias("/products/{id}", "product-detail")
+      .insert_route("products", "/products/all")
+      .insert_route_with_priority("api", "/api/{path}", -1)
+      .insert_route("section-page", "/{section}/{page}");
+
+    assert_eq!(router.parse_route("/product/all").0, Some("product-detail"));
+    assert_eq!(
+      router.parse_route("/products/all").0,
+      Some("product-detail")
+    );
+    assert_eq!(router.parse_route("/api/users").0, Some("section-page"));
+
+    let route_keys: Vec<_> = router
+      .parse_route_all("/api/users")
+      .into_iter()
+      .map(|(route_key, _route_parameters)| route_key)
+      .collect();
+    assert_eq!(route_keys, vec!["section-page", "api"]);
+    assert!(router
+      .dump_tree()
+      .contains("{} \"\" => \"product-detail\" (priority 1)\n"));
+
+    let mut router = Router::new();
+
+    router
+      .insert_route("api", "/api/{path}")
+      .insert_route("section-page", "/{section}/{page}");
+
+    assert_eq!(router.parse_route("/api/users").0, Some("api"));
+  }
+
+  #[test]
+  fn router_maximum_parameter_value_length() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("product-detail", "/product/{id}/detail")
+      .insert_route("product", "/product/{id}");
+
+    let id = "a".repeat(200);
+    let path = format!("/product/{}/detail", id);
+
+    let (route_key, route_parameters) = router.parse_route(&path);
+    assert_eq!(route_key, Some("product-detail"));
+    assert_eq!(route_parameters["id"], id);
+
+    router.set_maximum_parameter_value_length(50);
+
+    // without the limit, the path matches the product-detail route, the product route is never
+    // considered because the id would be even longer
+    let error = router.try_parse_route(&path).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "value of parameter id is 200 bytes, longer than the maximum of 50"
+    );
+    let ParseRouteError::ParameterValueTooLong {
+      route_key,
+      parameter_name,
+      maximum_length,
+      ..
+    } = error
+    else {
+      panic!("expected a parameter value too long error");
+    };
+    assert_eq!(route_key, "product-detail");
+    assert_eq!(parameter_name, "id");
+    assert_eq!(maximum_length, 50);
+
+    let (route_key, _route_parameters) = router.parse_route(&path);
+    assert_eq!(route_key, None);
+
+    let (route_key, _route_parameters) = router.parse_route(&format!("/product/{}", id));
+    assert_eq!(route_key, None);
+
+    let (route_key, _route_parameters) = router.parse_route("/not-found");
+    assert_eq!(route_key, None);
+
+    router.set_maximum_parameter_value_length(None);
+
+    let (route_key, _route_parameters) = router.parse_route(&path);
+    assert_eq!(route_key, Some("product-detail"));
+  }
+
+  #[test]
+  fn router_parameter_maximum_value_length() {
+    let mut router = Router::new();
+
+    router
+      .set_parameter_maximum_value_length("id", 3)
+      .insert_route("item", "/item/{id}")
+      .insert_alias("/items/{id}", "item");
+
+    let (route_key, _route_parameters) = router.parse_route("/item/123");
+    assert_eq!(route_key, Some("item"));
+
+    let error = router.try_parse_route("/items/1234").unwrap_err();
+    assert!(matches!(
+      error,
+      ParseRouteError::ParameterValueTooLong {
+        route_key: "item",
+        parameter_name: "id",
+        maximum_length: 3,
+        ..
+      }
+    ));
+
+    let trace = router.explain_parse("/item/1234");
+    assert_eq!(trace.route_key, None);
+    assert!(trace
+      .events
+      .contains(&ParseTraceEvent::ParameterValueTooLong {
+        depth: 3,
+        value_length: 4,
+        maximum_length: 3,
+      }));
+
+    // when the value is too long, the next route is tried
+    router.insert_route("section-page", "/{section}/{page}");
+
+    let (route_key, route_parameters) = router.parse_route("/item/1234");
+    assert_eq!(route_key, Some("section-page"));
+    assert_eq!(route_parameters["page"], "1234");
+
+    let route_keys: Vec<_> = router
+      .parse_route_all("/item/123")
+      .into_iter()
+      .map(|(route_key, _route_parameters)| route_key)
+      .collect();
+    assert_eq!(route_keys, vec!["item", "section-page"]);
+  }
+
+  #[test]
+  fn router_parameter_maximum_value_length_after_insert() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("item", "/item/{id}")
+      .insert_alias("/items/{id}", "item")
+      .set_parameter_maximum_value_length("id", 3);
+
+    let (route_key, _route_parameters) = router.parse_route("/item/123");
+    assert_eq!(route_key, Some("item"));
+
+    for path in ["/item/1234", "/items/1234"] {
+      assert!(matches!(
+        router.try_parse_route(path),
+        Err(ParseRouteError::ParameterValueTooLong {
+          route_key: "item",
+          parameter_name: "id",
+          maximum_length: 3,
+          ..
+        })
+      ));
+    }
+
+    // the router is the same as when the limit was set before inserting
+    let mut other_router = Router::new();
+    other_router
+      .set_parameter_maximum_value_length("id", 3)
+      .insert_route("item", "/item/{id}")
+      .insert_alias("/items/{id}", "item");
+    assert_eq!(router.trie(), other_router.trie());
+
+    router.set_parameter_maximum_value_length("id", None);
+
+    let (route_key, _route_parameters) = router.parse_route("/items/1234");
+    assert_eq!(route_key, Some("item"));
+  }
+
+  #[test]
+  fn router_parameter_anchors() {
+    let templates = [
+      "/file/{name}.json{version}",
+      "/file/{name}.xml{version}",
+      "/file/{name}-{version}",
+      "/file/{name}~{version}",
+      "/file/{name}@{version}",
+    ];
+    let paths = [
+      (
+        "/file/a.json1",
+        Some(("/file/{name}.json{version}", "a", "1")),
+      ),
+      ("/file/a.XML1", None),
+      ("/file/a-b~c", Some(("/file/{name}-{version}", "a", "b~c"))),
+      ("/file/a~b-c", Some(("/file/{name}-{version}", "a~b", "c"))),
+      ("/file/a~b@c", Some(("/file/{name}@{version}", "a~b", "c"))),
+      ("/file/a.b~c", Some(("/file/{name}~{version}", "a.b", "c"))),
+      ("/file/a.b", None),
+    ];
+
+    let mut router = Router::new();
+    for template in templates {
+      router.insert_route(template, template);
+    }
+
+    for (path, expected) in paths {
+      let (route_key, route_parameters) = router.parse_route(path);
+      assert_eq!(
+        route_key.map(|route_key| (
+          route_key,
+          route_parameters["name"].as_ref(),
+          route_parameters["version"].as_ref()
+        )),
+        expected,
+        "{}",
+        path
+      );
+
+      // parse_route_all never uses the searcher for the anchors of the parameter children
+      let routes = router.parse_route_all(path);
+      assert_eq!(
+        routes
+          .first()
+          .map(|(route_key, _route_parameters)| *route_key),
+        route_key,
+        "{}",
+        path
+      );
+    }
+
+    let mut router = Router::new();
+    router.set_ignore_ascii_case(true);
+    for template in templates {
+      router.insert_route(template, template);
+    }
+
+    let (route_key, _route_parameters) = router.parse_route("/file/a.XML1");
+    assert_eq!(route_key, Some("/file/{name}.xml{version}"));
+
+    // when ignoring ascii case, parse_route uses the searcher
+    for (path, _expected) in paths {
+      let (route_key, _route_parameters) = router.parse_route(path);
+      let routes = router.parse_route_all(path);
+      assert_eq!(
+        routes
+          .first()
+          .map(|(route_key, _route_parameters)| *route_key),
+        route_key,
+        "{}",
+        path
+      );
+    }
+
+    router.set_maximum_parameter_value_length(3);
+
+    let (route_key, _route_parameters) = router.parse_route("/file/abc@1");
+    assert_eq!(route_key, Some("/file/{name}@{version}"));
+
+    let error = router.try_parse_route("/file/abcd@1").unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "value of parameter name is 4 bytes, longer than the maximum of 3"
+    );
+  }
+
+  #[test]
+  fn router_deep_template() {
+    // a node for every segment, parsing does not recurse so this does not overflow the stack
+    let segment_count = 10_000;
+    let template: String = (0..segment_count)
+      .map(|index| format!("/{{p{}}}", index))
+      .collect();
+    let parameter_names: Vec<_> = (0..segment_count)
+      .map(|index| format!("p{}", index))
+      .collect();
+
+    let mut router = Router::new();
+    router
+      .insert_route("deep", &template)
+      .insert_route("deep-all", "/all");
+
+    let path: String = (0..segment_count)
+      .map(|index| format!("/{}", index))
+      .collect();
+    let (route_key, route_parameters) = router.parse_route(&path);
+    assert_eq!(route_key, Some("deep"));
+    assert_eq!(route_parameters.len(), segment_count);
+    assert_eq!(route_parameters[parameter_names[0].as_str()], "0");
+    assert_eq!(route_parameters[parameter_names[9_999].as_str()], "9999");
+
+    // the path does not match when the last segment is missing, after going all the way down
+    let (route_key, _route_parameters) = router.parse_route(&path[..path.rfind('/').unwrap()]);
+    assert_eq!(route_key, None);
+
+    let routes = router.parse_route_all(&path);
+    assert_eq!(routes.len(), 1);
+
+    // comparing the tries does not recurse either
+    assert_eq!(router.trie(), router.clone().trie());
+
+    // and neither does dumping them
+    let tree = router.dump_tree();
+    assert!(tree.lines().count() > segment_count);
+    assert!(tree.contains("=> \"deep\"\n"));
+    assert_eq!(format!("{:?}", router.trie()), tree);
+    let dot = router.to_dot();
+    assert!(dot.lines().count() > 2 * segment_count);
+    assert!(dot.ends_with("}\n"));
+  }
+
+  #[test]
+  fn router_maximum_parse_steps() {
+    let mut router = Router::new();
+
+    router
+      .set_maximum_parse_steps(2)
+      .insert_route("b", "/a/{x}/b");
+
+    assert!(matches!(
+      router.try_parse_route("/a/1/b"),
+      Err(ParseRouteError::TooManySteps { maximum_steps: 2 })
+    ));
+    assert_eq!(router.parse_route("/a/1/b").0, None);
+    assert!(router.parse_route_all("/a/1/b").is_empty());
+    assert!(router
+      .explain_parse("/a/1/b")
+      .events
+      .contains(&ParseTraceEvent::TooManySteps {
+        depth: 2,
+        maximum_steps: 2,
+      }));
+
+    let error = router.try_parse_route("/a/1/b").unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "parsing the path took the maximum of 2 steps"
+    );
+
+    router.set_maximum_parse_steps(3);
+    assert_eq!(router.parse_route("/a/1/b").0, Some("b"));
+
+    router.set_maximum_parse_steps(None);
+    assert_eq!(router.parse_route("/a/1/b").0, Some("b"));
+  }
+
+  #[test]
+  fn router_maximum_parse_steps_adversarial() {
+    // every node is matched at most once, so a parse never takes more steps than there are
+    // nodes, whatever the path
+    let templates = read_fixture("github");
+    let templates = read_template_lines(&templates);
+
+    let mut router = Router::new();
+    for template in templates.iter().cloned() {
+      router.insert_route(template, template);
+    }
+    router.set_maximum_parse_steps(router.nodes.len());
+
+    let mut pieces: Vec<String> = templates
+      .iter()
+      .flat_map(|template| TEMPLATE_PLACEHOLDER_REGEX.split(template))
+      .filter(|piece| !piece.is_empty())
+      .map(|piece| piece.to_owned())
+      .collect();
+    pieces.extend(["/", "-", ".", "%", "{", "}"].map(|piece| piece.to_owned()));
+    pieces.push("/".repeat(64));
+    pieces.push("a".repeat(256));
+    pieces.push("/a".repeat(64));
+
+    // a xorshift generator, so the paths are the same on every run
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = |bound: usize| {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+      (state % bound as u64) as usize
+    };
+
+    for _ in 0..10_000 {
+      let piece_count = 1 + next(16);
+      let path: String = (0..piece_count)
+        .map(|_| pieces[next(pieces.len())].as_str())
+        .collect();
+
+      assert!(
+        !matches!(
+          router.try_parse_route(&path),
+          Err(ParseRouteError::TooManySteps { .. })
+        ),
+        "too many steps for {}",
+        path
+      );
+    }
+  }
+
+  #[test]
+  fn router_dump() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("product-detail", "/product/{id}")
+      .insert_alias("/p/{id}", "product-detail");
+
+    assert_eq!(
+      router.dump_tree(),
+      r#"""
+  "/p"
+    "roduct/"
+      {} "" => "product-detail"
+    "/"
+      {} "" => "product-detail" (alias)
+"#
+    );
+
+    let dot = router.to_dot();
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(
+      dot.contains(r#"[label="{} \"\"\n=> \"product-detail\" (alias)", shape=doubleoctagon]"#)
+    );
+    assert!(dot.ends_with("}\n"));
+  }
+
+  #[test]
+  fn router_explain_parse() {
+    let mut router = Router::new();
+
+    router
+      .set_maximum_parameter_value_length(2)
+      .insert_route("a", "/a")
+      .insert_route("c", "/b/{x}/c");
+
+    let trace = router.explain_parse("/b/12/c");
+    assert_eq!(trace.route_key, Some("c"));
+    assert_eq!(
+      trace.to_string(),
+      r#"parse "/b/12/c"
+visit "" at 0
+  anchor matched
+  visit "/" at 0
+    anchor matched
+    visit "b/" at 1
+      anchor matched
+      visit {} "/c" at 3
+        anchor found after 2 bytes
+        route "c" matched
+      child 0 matched
+    child 0 matched
+  child 0 matched
+"#
+    );
+
+    let trace = router.explain_parse("/b/123/c");
+    assert_eq!(trace.route_key, None);
+    assert!(trace.events.contains(&ParseTraceEvent::AnchorNotFound {
+      depth: 3,
+      searched_length: 4,
+      limited: true,
+    }));
+  }
+
+  #[test]
+  fn router_try_insert_route() {
+    let mut router = Router::new();
+
+    router
+      .insert_route("a", "/a/{x}")
+      .insert_route("b", "/b/{x}/c");
+
+    assert_eq!(
+      router.try_insert_route("c", "/a/{y}").err(),
+      Some(InsertRouteError::AmbiguousRoute { route_key: "a" })
+    );
+    assert_eq!(
+      router.try_insert_route("d", "/b/{y}/c").err(),
+      Some(InsertRouteError::AmbiguousRoute { route_key: "b" })
+    );
+    assert_eq!(
+      router.try_insert_alias("/c/{y}", "c").err(),
+      Some(InsertRouteError::UnknownRoute)
+    );
+
+    let routes: Vec<_> = router.routes().map(|(route_key, ..)| route_key).collect();
+    assert_eq!(routes, vec!["a", "b"]);
+
+    let (route_key, route_parameters) = router.parse_route("/a/1");
+    assert_eq!(route_key, Some("a"));
+    assert_eq!(route_parameters["x"], "1");
+  }
+
+  #[derive(Deserialize)]
+  struct Conformance {
+    suites: Vec<ConformanceSuite>,
+  }
+
+  #[derive(Deserialize)]
+  struct ConformanceSuite {
+    name: String,
+    routes: Vec<ConformanceRoute>,
+    cases: Vec<ConformanceCase>,
+  }
+
+  #[derive(Deserialize)]
+  struct ConformanceRoute {
+    key: String,
+    template: String,
+  }
+
+  #[derive(Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct ConformanceCase {
+    path: String,
+    route_key: Option<String>,
+    route_parameters: HashMap<String, String>,
+    stringified_path: Option<String>,
+  }
+
+  // the cases that are shared with the other implementations
+  #[test]
+  fn router_conformance() {
+    let conformance = std::fs::read_to_string(fixture_path("conformance.json")).unwrap();
+    let conformance: Conformance = serde_json::from_str(&conformance).unwrap();
+
+    for suite in conformance.suites.iter() {
+      let mut router = Router::new();
+      for route in suite.routes.iter() {
+        router.insert_route(route.key.as_str(), &route.template);
+      }
+
+      for case in suite.cases.iter() {
+        let message = format!("{} {}", suite.name, case.path);
+
+        let (route_key, route_parameters) = router.parse_route(&case.path);
+        assert_eq!(route_key, case.route_key.as_deref(), "{}", message);
+
+        let route_parameters: HashMap<_, _> = route_parameters
+          .iter()
+          .map(|(name, value)| (*name, value.as_ref()))
+          .collect();
+        let expected_route_parameters: HashMap<_, _> = case
+          .route_parameters
+          .iter()
+          .map(|(name, value)| (name.as_str(), value.as_str()))
+          .collect();
+        assert_eq!(route_parameters, expected_route_parameters, "{}", message);
+
+        let stringified_path =
+          route_key.and_then(|route_key| router.stringify_route(route_key, &route_parameters));
+        assert_eq!(
+          stringified_path.as_deref(),
+          case.stringified_path.as_deref(),
+          "{}",
+          message
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn router_templates_small() {
+    router_templates("small")
+  }
+
+  #[test]
+  fn router_templates_docker() {
+    router_templates("docker")
+  }
+
+  #[test]
+  fn router_templates_github() {
+    router_templates("github")
+  }
+
+  fn router_templates(name: &str) {
+    let templates = read_fixture(name);
+    let templates = read_template_lines(&templates);
 
     let mut all_parameter_names: HashSet<&str> = Default::default();
 
@@ -379,6 +2346,35 @@ mod tests {
 
       assert_eq!(route_key, Some(template));
       assert_eq!(route_parameters, expected_parameters);
+
+      let routes = router.parse_route_all(path);
+      assert_eq!(routes.first(), Some(&(template, route_parameters)));
     }
   }
+
+  // failures are not persisted, the runner does not know the source file
+  fn round_trip_runner() -> TestRunner {
+    TestRunner::new(ProptestConfig {
+      failure_persistence: None,
+      ..ProptestConfig::default()
+    })
+  }
+
+  fn fixture_path(file_name: &str) -> std::path::PathBuf {
+    let mut path = std::path::PathBuf::new();
+    path.push("..");
+    path.push("..");
+    path.push("..");
+    path.push("fixtures");
+    path.push(file_name);
+
+    path
+  }
+
+  fn read_fixture(name: &str) -> String {
+    let mut path = fixture_path(name);
+    path.set_extension("txt");
+
+    std::fs::read_to_string(path.as_path()).unwrap()
+  }
 }
@@ -1,50 +1,115 @@
-use std::cmp;
+use std::borrow::Cow;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
 
-pub fn find_common_prefix_length(chars_left: &Vec<char>, chars_right: &Vec<char>) -> usize {
-  let common_length = cmp::min(chars_left.len(), chars_right.len());
-
-  let mut index = 0;
-  while index < common_length {
-    let char_left = chars_left[index];
-    let char_right = chars_right[index];
-    if char_left != char_right {
-      break;
+// returns the length, in bytes, of the common prefix of both strings. The length is always on a
+// char boundary of both strings
+pub fn find_common_prefix_length(left: &str, right: &str, ignore_ascii_case: bool) -> usize {
+  for ((index, char_left), char_right) in left.char_indices().zip(right.chars()) {
+    if !chars_equal(char_left, char_right, ignore_ascii_case) {
+      return index;
     }
+  }
+
+  if left.len() < right.len() {
+    left.len()
+  } else {
+    right.len()
+  }
+}
 
-    index += 1;
+pub fn str_equals(left: &str, right: &str, ignore_ascii_case: bool) -> bool {
+  if ignore_ascii_case {
+    left.eq_ignore_ascii_case(right)
+  } else {
+    left == right
   }
+}
 
+pub fn str_starts_with(haystack: &str, needle: &str, ignore_ascii_case: bool) -> bool {
+  if ignore_ascii_case {
+    haystack.len() >= needle.len()
+      && haystack.as_bytes()[..needle.len()].eq_ignore_ascii_case(needle.as_bytes())
+  } else {
+    haystack.starts_with(needle)
+  }
+}
+
+pub fn str_find(haystack: &str, needle: &str, ignore_ascii_case: bool) -> Option<usize> {
+  if needle.is_empty() {
+    Some(0)
+  } else if ignore_ascii_case {
+    // ascii bytes are never part of a multi byte char, so every match starts on a char boundary
+    haystack
+      .as_bytes()
+      .windows(needle.len())
+      .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+  } else {
+    haystack.find(needle)
+  }
+}
+
+// the largest index that is not greater than `index` and is on a char boundary
+pub fn floor_char_boundary(value: &str, index: usize) -> usize {
+  if index >= value.len() {
+    return value.len();
+  }
+
+  let mut index = index;
+  while !value.is_char_boundary(index) {
+    index -= 1;
+  }
   index
 }
 
+pub fn normalize_unicode(value: &str) -> Cow<'_, str> {
+  if is_nfc_quick(value.chars()) == IsNormalized::Yes {
+    Cow::Borrowed(value)
+  } else {
+    Cow::Owned(value.nfc().collect())
+  }
+}
+
+fn chars_equal(left: char, right: char, ignore_ascii_case: bool) -> bool {
+  if ignore_ascii_case {
+    left.eq_ignore_ascii_case(&right)
+  } else {
+    left == right
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
   #[test]
   fn common_prefix_length_test() {
-    assert_eq!(
-      find_common_prefix_length(
-        &String::from("ab").chars().collect(),
-        &String::from("abc").chars().collect()
-      ),
-      2
-    );
-
-    assert_eq!(
-      find_common_prefix_length(
-        &String::from("abc").chars().collect(),
-        &String::from("abc").chars().collect()
-      ),
-      3
-    );
-
-    assert_eq!(
-      find_common_prefix_length(
-        &String::from("bc").chars().collect(),
-        &String::from("abc").chars().collect()
-      ),
-      0,
-    );
+    assert_eq!(find_common_prefix_length("ab", "abc", false), 2);
+    assert_eq!(find_common_prefix_length("abc", "abc", false), 3);
+    assert_eq!(find_common_prefix_length("bc", "abc", false), 0);
+    assert_eq!(find_common_prefix_length("aB", "Abc", false), 0);
+    assert_eq!(find_common_prefix_length("aB", "Abc", true), 2);
+    assert_eq!(find_common_prefix_length("/é/a", "/é/b", false), 4);
+    assert_eq!(find_common_prefix_length("/éa", "/èa", false), 1);
+  }
+
+  #[test]
+  fn find_test() {
+    assert_eq!(str_find("/a/B/c", "/b", false), None);
+    assert_eq!(str_find("/a/B/c", "/b", true), Some(2));
+    assert_eq!(str_find("/é/B", "/b", true), Some(3));
+    assert_eq!(str_find("", "", true), Some(0));
+  }
+
+  #[test]
+  fn floor_char_boundary_test() {
+    assert_eq!(floor_char_boundary("aé", 2), 1);
+    assert_eq!(floor_char_boundary("aé", 3), 3);
+    assert_eq!(floor_char_boundary("aé", 10), 3);
+  }
+
+  #[test]
+  fn normalize_unicode_test() {
+    assert!(matches!(normalize_unicode("/caf\u{e9}"), Cow::Borrowed(_)));
+    assert_eq!(normalize_unicode("/cafe\u{301}"), "/caf\u{e9}");
   }
 }
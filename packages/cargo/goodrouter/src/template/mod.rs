@@ -6,3 +6,12 @@ pub mod template_parts;
 
 pub static TEMPLATE_PLACEHOLDER_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r"\{(.*?)\}").unwrap());
+
+// the templates in a file with a template per line, like the fixtures
+pub fn read_template_lines(templates: &str) -> Vec<&str> {
+  templates
+    .split('\n')
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .collect()
+}
@@ -33,12 +33,12 @@ impl<'r> Iterator for TemplatePairs<'r> {
       return None;
     }
 
+    // the parts alternate between anchors and parameters, and always end with an anchor
     let result = if let Some(part) = self.parts.next() {
       if self.index == 0 {
         Some((part, None))
       } else {
-        let anchor = self.parts.next().unwrap();
-        Some((anchor, Some(part)))
+        self.parts.next().map(|anchor| (anchor, Some(part)))
       }
     } else {
       None
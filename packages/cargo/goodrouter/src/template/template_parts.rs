@@ -40,17 +40,21 @@ impl<'r> Iterator for TemplateParts<'r> {
       return None;
     }
 
-    let result = if self.index % 2 == 0 {
+    let result = if self.index & 1 == 0 {
       let part_offset = self.part_offset;
 
-      if let Some(current_match) = self.matches.next() {
-        let first_capture = current_match.get(0).unwrap();
-        let current_capture = current_match.get(1).unwrap();
+      // the name of the parameter is the first group, if the regex does not have a first group,
+      // or it did not participate in the match, the whole placeholder is the name
+      let current_match = self.matches.next().and_then(|captures| {
+        let placeholder = captures.get(0)?;
+        Some((placeholder, captures.get(1).unwrap_or(placeholder)))
+      });
 
-        self.part_offset = first_capture.end();
-        self.parameter = current_capture.as_str();
+      if let Some((placeholder, parameter)) = current_match {
+        self.part_offset = placeholder.end();
+        self.parameter = parameter.as_str();
 
-        Some(&self.template[part_offset..first_capture.start()])
+        Some(&self.template[part_offset..placeholder.start()])
       } else {
         self.is_finished = true;
 
@@ -85,4 +89,17 @@ mod tests {
 
     assert_eq!(parts, vec![""])
   }
+
+  #[test]
+  fn parse_template_parts_without_group() {
+    let re = Regex::new(r":\w+").unwrap();
+    let parts: Vec<_> = parse_template_parts("/a/:b/c", &re).collect();
+
+    assert_eq!(parts, vec!["/a/", ":b", "/c"]);
+
+    let re = Regex::new(r"\{(\w+)\}|\*").unwrap();
+    let parts: Vec<_> = parse_template_parts("/a/{b}/*", &re).collect();
+
+    assert_eq!(parts, vec!["/a/", "b", "/", "*", ""]);
+  }
 }
@@ -0,0 +1,579 @@
+use crate::router::Router as RouteTable;
+use ::tower::{util::BoxCloneService, Layer, Service, ServiceExt};
+use http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use std::{
+  collections::HashMap,
+  future::Future,
+  hash::Hash,
+  mem,
+  pin::Pin,
+  sync::Arc,
+  task::{Context, Poll},
+};
+
+/// The route that matched the path of a request. The layer and the router insert it in the
+/// extensions of the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedRoute<K> {
+  pub route_key: K,
+  pub route_parameters: Arc<HashMap<&'static str, String>>,
+}
+
+/// The parameters of the `MatchedRoute`, they are also in the extensions of the request so they
+/// can be read without knowing the type of the route key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedParameters(pub Arc<HashMap<&'static str, String>>);
+
+pub type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
+
+// parse the path of the request, and insert the match in the extensions. If the request does not
+// go to a route the error is the response, not found, bad request or a redirect
+fn match_request<K, ReqBody, ResBody>(
+  route_table: &RouteTable<'static, K>,
+  request: &mut Request<ReqBody>,
+) -> Result<K, Response<ResBody>>
+where
+  K: Eq + Hash + Copy + Send + Sync + 'static,
+  ResBody: Default,
+{
+  let route_match = match route_table.match_route(request.uri().path()) {
+    Ok(Some(route_match)) => route_match,
+    Ok(None) => return Err(status_response(StatusCode::NOT_FOUND)),
+    // a parameter value could not be decoded or is too long, or the path took too many steps
+    Err(_error) => return Err(status_response(StatusCode::BAD_REQUEST)),
+  };
+
+  if route_match.redirect {
+    let mut location = route_match.canonical_path.into_owned();
+    if let Some(query) = request.uri().query() {
+      location.push('?');
+      location.push_str(query);
+    }
+
+    let mut response = status_response(StatusCode::PERMANENT_REDIRECT);
+    if let Ok(location) = HeaderValue::try_from(location) {
+      response.headers_mut().insert(header::LOCATION, location);
+    }
+    return Err(response);
+  }
+
+  let route_key = route_match.route_key;
+  let route_parameters: Arc<HashMap<_, _>> = Arc::new(
+    route_match
+      .route_parameters
+      .into_iter()
+      .map(|(parameter_name, parameter_value)| (parameter_name, parameter_value.into_owned()))
+      .collect(),
+  );
+  let extensions = request.extensions_mut();
+  extensions.insert(MatchedParameters(route_parameters.clone()));
+  extensions.insert(MatchedRoute {
+    route_key,
+    route_parameters,
+  });
+
+  Ok(route_key)
+}
+
+fn status_response<B: Default>(status: StatusCode) -> Response<B> {
+  let mut response = Response::new(B::default());
+  *response.status_mut() = status;
+  response
+}
+
+/// A layer that matches the path of every request. When a route matches, the `MatchedRoute` is
+/// inserted in the extensions of the request and the request goes to the inner service,
+/// otherwise the response is 404 Not Found, 400 Bad Request if the path is invalid, or a 308
+/// redirect to the canonical path.
+pub struct GoodrouterLayer<K> {
+  route_table: Arc<RouteTable<'static, K>>,
+}
+
+impl<K> GoodrouterLayer<K> {
+  pub fn new(route_table: impl Into<Arc<RouteTable<'static, K>>>) -> Self {
+    Self {
+      route_table: route_table.into(),
+    }
+  }
+}
+
+impl<K> Clone for GoodrouterLayer<K> {
+  fn clone(&self) -> Self {
+    Self {
+      route_table: self.route_table.clone(),
+    }
+  }
+}
+
+impl<K, S> Layer<S> for GoodrouterLayer<K> {
+  type Service = GoodrouterService<K, S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    GoodrouterService {
+      route_table: self.route_table.clone(),
+      inner,
+    }
+  }
+}
+
+/// The service of `GoodrouterLayer`.
+pub struct GoodrouterService<K, S> {
+  route_table: Arc<RouteTable<'static, K>>,
+  inner: S,
+}
+
+impl<K, S: Clone> Clone for GoodrouterService<K, S> {
+  fn clone(&self) -> Self {
+    Self {
+      route_table: self.route_table.clone(),
+      inner: self.inner.clone(),
+    }
+  }
+}
+
+impl<K, S, ReqBody, ResBody> Service<Request<ReqBody>> for GoodrouterService<K, S>
+where
+  K: Eq + Hash + Copy + Send + Sync + 'static,
+  S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone,
+  S::Future: Send + 'static,
+  ResBody: Default + Send + 'static,
+{
+  type Response = Response<ResBody>;
+  type Error = S::Error;
+  type Future = BoxFuture<Self::Response, Self::Error>;
+
+  fn poll_ready(&mut self, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.inner.poll_ready(context)
+  }
+
+  fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+    // take the inner service that is ready, and leave a clone for the next request. If the inner
+    // service is not called it is dropped, so it does not stay ready
+    let clone = self.inner.clone();
+    let mut inner = mem::replace(&mut self.inner, clone);
+
+    if let Err(response) = match_request(&self.route_table, &mut request) {
+      return Box::pin(async { Ok(response) });
+    }
+
+    Box::pin(inner.call(request))
+  }
+}
+
+type RouteService<ReqBody, ResBody, E> = BoxCloneService<Request<ReqBody>, Response<ResBody>, E>;
+
+// the services of a route, by method
+struct RouteServices<ReqBody, ResBody, E> {
+  method_services: Vec<(Method, RouteService<ReqBody, ResBody, E>)>,
+  // the service for the methods that do not have a service of their own
+  any_service: Option<RouteService<ReqBody, ResBody, E>>,
+}
+
+impl<ReqBody, ResBody, E> Clone for RouteServices<ReqBody, ResBody, E> {
+  fn clone(&self) -> Self {
+    Self {
+      method_services: self.method_services.clone(),
+      any_service: self.any_service.clone(),
+    }
+  }
+}
+
+/// A service that routes every request to the service of the route that matches the path, with
+/// the `MatchedRoute` in the extensions of the request. The response is 404 Not Found if no route
+/// matches, or 405 Method Not Allowed if the route has no service for the method. Like the layer,
+/// invalid paths are 400 Bad Request and redirects to the canonical path are 308.
+pub struct Router<K, ReqBody, ResBody, E> {
+  route_table: Arc<RouteTable<'static, K>>,
+  route_services: HashMap<K, RouteServices<ReqBody, ResBody, E>>,
+}
+
+impl<K, ReqBody, ResBody, E> Router<K, ReqBody, ResBody, E>
+where
+  K: Eq + Hash + Copy + Send + Sync + 'static,
+  ReqBody: 'static,
+  ResBody: 'static,
+  E: 'static,
+{
+  pub fn new(route_table: impl Into<Arc<RouteTable<'static, K>>>) -> Self {
+    Self {
+      route_table: route_table.into(),
+      route_services: HashMap::new(),
+    }
+  }
+
+  /// Route requests for the route with any method to the service, except for the methods that
+  /// have a service of their own.
+  pub fn route<S>(&mut self, route_key: K, service: S) -> &mut Self
+  where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>, Error = E> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+  {
+    self.route_services_mut(route_key).any_service = Some(BoxCloneService::new(service));
+    self
+  }
+
+  /// Route requests for the route with the method to the service.
+  pub fn route_method<S>(&mut self, route_key: K, method: Method, service: S) -> &mut Self
+  where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>, Error = E> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+  {
+    let route_services = self.route_services_mut(route_key);
+    route_services
+      .method_services
+      .retain(|(other_method, _service)| *other_method != method);
+    route_services
+      .method_services
+      .push((method, BoxCloneService::new(service)));
+    self
+  }
+
+  fn route_services_mut(&mut self, route_key: K) -> &mut RouteServices<ReqBody, ResBody, E> {
+    self
+      .route_services
+      .entry(route_key)
+      .or_insert_with(|| RouteServices {
+        method_services: Vec::new(),
+        any_service: None,
+      })
+  }
+}
+
+impl<K: Clone, ReqBody, ResBody, E> Clone for Router<K, ReqBody, ResBody, E> {
+  fn clone(&self) -> Self {
+    Self {
+      route_table: self.route_table.clone(),
+      route_services: self.route_services.clone(),
+    }
+  }
+}
+
+impl<K, ReqBody, ResBody, E> Service<Request<ReqBody>> for Router<K, ReqBody, ResBody, E>
+where
+  K: Eq + Hash + Copy + Send + Sync + 'static,
+  ReqBody: Send + 'static,
+  ResBody: Default + Send + 'static,
+  E: Send + 'static,
+{
+  type Response = Response<ResBody>;
+  type Error = E;
+  type Future = BoxFuture<Self::Response, Self::Error>;
+
+  // the services are cloned for every request, and polled when the request is handled
+  fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+    let route_key = match match_request(&self.route_table, &mut request) {
+      Ok(route_key) => route_key,
+      Err(response) => return Box::pin(async { Ok(response) }),
+    };
+    let Some(route_services) = self.route_services.get(&route_key) else {
+      return Box::pin(async { Ok(status_response(StatusCode::NOT_FOUND)) });
+    };
+
+    let service = route_services
+      .method_services
+      .iter()
+      .find(|(method, _service)| method == request.method())
+      .map(|(_method, service)| service)
+      .or(route_services.any_service.as_ref());
+    let Some(service) = service else {
+      let mut response = status_response(StatusCode::METHOD_NOT_ALLOWED);
+      let allow = route_services
+        .method_services
+        .iter()
+        .map(|(method, _service)| method.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+      if let Ok(allow) = HeaderValue::from_str(&allow) {
+        response.headers_mut().insert(header::ALLOW, allow);
+      }
+      return Box::pin(async { Ok(response) });
+    };
+
+    Box::pin(service.clone().oneshot(request))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::path_normalization::TrailingSlashPolicy;
+  use ::tower::{service_fn, ServiceBuilder};
+  use std::{
+    convert::Infallible,
+    future,
+    sync::atomic::{AtomicUsize, Ordering},
+  };
+
+  // responds with the route key and the parameters, in the order of the names
+  fn echo(
+    parameter_names: &'static [&'static str],
+  ) -> impl Service<
+    Request<String>,
+    Response = Response<String>,
+    Error = Infallible,
+    Future = impl Send + 'static,
+  > + Clone {
+    service_fn(move |request: Request<String>| async move {
+      let route_match = request.extensions().get::<MatchedRoute<&str>>().unwrap();
+      let route_parameters = request.extensions().get::<MatchedParameters>().unwrap();
+      assert_eq!(route_parameters.0, route_match.route_parameters);
+      let mut body = format!("{} {}", request.method(), route_match.route_key);
+      for parameter_name in parameter_names {
+        body.push(' ');
+        body.push_str(&route_match.route_parameters[parameter_name]);
+      }
+      Ok::<_, Infallible>(Response::new(body))
+    })
+  }
+
+  // counts the clones of the service that are ready, but not called
+  struct ReadyCounter {
+    ready_count: Arc<AtomicUsize>,
+    ready: bool,
+  }
+
+  impl Clone for ReadyCounter {
+    fn clone(&self) -> Self {
+      Self {
+        ready_count: self.ready_count.clone(),
+        ready: false,
+      }
+    }
+  }
+
+  impl Drop for ReadyCounter {
+    fn drop(&mut self) {
+      if self.ready {
+        self.ready_count.fetch_sub(1, Ordering::SeqCst);
+      }
+    }
+  }
+
+  impl Service<Request<String>> for ReadyCounter {
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+      if !self.ready {
+        self.ready = true;
+        self.ready_count.fetch_add(1, Ordering::SeqCst);
+      }
+      Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _request: Request<String>) -> Self::Future {
+      assert!(self.ready);
+      self.ready = false;
+      self.ready_count.fetch_sub(1, Ordering::SeqCst);
+      future::ready(Ok(Response::new(String::new())))
+    }
+  }
+
+  fn request(method: Method, uri: &str) -> Request<String> {
+    Request::builder()
+      .method(method)
+      .uri(uri)
+      .body(String::new())
+      .unwrap()
+  }
+
+  fn route_table() -> RouteTable<'static, &'static str> {
+    let mut route_table = RouteTable::new();
+    route_table
+      .insert_route("all-products", "/product/all")
+      .insert_route("product-detail", "/product/{id}")
+      .insert_route("product-tab", "/product/{id}/{tab}");
+    route_table
+  }
+
+  #[tokio::test]
+  async fn tower_router() {
+    let mut router = Router::new(route_table());
+    router
+      .route("all-products", echo(&[]))
+      .route_method("product-detail", Method::GET, echo(&["id"]))
+      .route_method("product-detail", Method::PUT, echo(&["id"]));
+
+    let response = router
+      .clone()
+      .oneshot(request(Method::GET, "/product/all?page=2"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "GET all-products");
+
+    let response = router
+      .clone()
+      .oneshot(request(Method::DELETE, "/product/all"))
+      .await
+      .unwrap();
+    assert_eq!(response.body(), "DELETE all-products");
+
+    let response = router
+      .clone()
+      .oneshot(request(Method::PUT, "/product/a%20b"))
+      .await
+      .unwrap();
+    assert_eq!(response.body(), "PUT product-detail a b");
+
+    let response = router
+      .clone()
+      .oneshot(request(Method::POST, "/product/1"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()[header::ALLOW], "GET, PUT");
+    assert_eq!(response.body(), "");
+
+    // the route is in the route table, but there is no service for it
+    let response = router
+      .clone()
+      .oneshot(request(Method::GET, "/product/1/info"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = router
+      .clone()
+      .oneshot(request(Method::GET, "/not-found"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
+  #[tokio::test]
+  async fn tower_layer() {
+    let service = ServiceBuilder::new()
+      .layer(GoodrouterLayer::new(route_table()))
+      .service(echo(&["id", "tab"]));
+
+    let response = service
+      .clone()
+      .oneshot(request(Method::GET, "/product/1/info"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "GET product-tab 1 info");
+
+    let response = service
+      .clone()
+      .oneshot(request(Method::GET, "/not-found"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
+  #[tokio::test]
+  async fn tower_router_invalid_paths() {
+    let mut route_table = RouteTable::new();
+    route_table
+      .set_trailing_slash_policy(TrailingSlashPolicy::Redirect)
+      .set_maximum_parameter_value_length(8)
+      .insert_route("product-detail", "/product/{id}/detail");
+
+    let mut router = Router::new(route_table);
+    router.route("product-detail", echo(&["id"]));
+
+    // the parameter value cannot be decoded
+    let response = router
+      .clone()
+      .oneshot(request(Method::GET, "/product/%FF/detail"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // the parameter value is too long
+    let response = router
+      .clone()
+      .oneshot(request(Method::GET, "/product/123456789/detail"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router
+      .clone()
+      .oneshot(request(Method::POST, "/product/1/detail/?page=2"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+      response.headers()[header::LOCATION],
+      "/product/1/detail?page=2"
+    );
+  }
+
+  #[tokio::test]
+  async fn tower_router_too_many_steps() {
+    let mut route_table = route_table();
+    route_table.set_maximum_parse_steps(1);
+
+    let mut router = Router::new(route_table);
+    router.route("product-tab", echo(&["id", "tab"]));
+
+    // parsing the path takes too many steps
+    let response = router
+      .clone()
+      .oneshot(request(Method::GET, "/product/1/info"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn tower_layer_invalid_paths() {
+    let mut route_table = RouteTable::new();
+    route_table
+      .set_trailing_slash_policy(TrailingSlashPolicy::Redirect)
+      .insert_route("product-detail", "/product/{id}/detail");
+
+    let service = ServiceBuilder::new()
+      .layer(GoodrouterLayer::new(route_table))
+      .service(echo(&["id"]));
+
+    let response = service
+      .clone()
+      .oneshot(request(Method::GET, "/product/%FF/detail"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = service
+      .clone()
+      .oneshot(request(Method::GET, "/product/1/detail/"))
+      .await
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(response.headers()[header::LOCATION], "/product/1/detail");
+  }
+
+  #[tokio::test]
+  async fn tower_layer_readiness() {
+    let ready_count = Arc::new(AtomicUsize::new(0));
+    let mut service = GoodrouterLayer::new(route_table()).layer(ReadyCounter {
+      ready_count: ready_count.clone(),
+      ready: false,
+    });
+
+    for (uri, status) in [
+      ("/product/1", StatusCode::OK),
+      ("/not-found", StatusCode::NOT_FOUND),
+    ] {
+      let response = service
+        .ready()
+        .await
+        .unwrap()
+        .call(request(Method::GET, uri))
+        .await
+        .unwrap();
+      assert_eq!(response.status(), status);
+
+      // the inner service does not stay ready, also not when the request does not go to it
+      assert_eq!(ready_count.load(Ordering::SeqCst), 0);
+    }
+  }
+}
@@ -0,0 +1,156 @@
+use crate::owned_router::OwnedRouter;
+use js_sys::{Array, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+// the routes of a router, as saved to and loaded from json
+#[derive(Serialize, Deserialize)]
+struct RouterJson {
+  routes: Vec<RouteJson>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RouteJson {
+  key: String,
+  template: String,
+}
+
+/// The router for javascript. Like the npm package the routes have string keys, and the methods
+/// have the same names, so both can be used from the same code.
+#[wasm_bindgen(js_name = Router)]
+pub struct WasmRouter {
+  router: OwnedRouter<usize>,
+  // the keys of the routes, the route key in the router is the index in this vec
+  route_keys: Vec<String>,
+  // the index of every key in `route_keys`
+  route_indices: HashMap<String, usize>,
+}
+
+#[wasm_bindgen(js_class = Router)]
+impl WasmRouter {
+  #[wasm_bindgen(constructor)]
+  #[allow(clippy::new_without_default)]
+  pub fn new() -> Self {
+    Self {
+      router: OwnedRouter::new(),
+      route_keys: Vec::new(),
+      route_indices: HashMap::new(),
+    }
+  }
+
+  /// Insert a route, throws if the route cannot be inserted.
+  #[wasm_bindgen(js_name = insertRoute)]
+  pub fn insert_route(&mut self, route_key: String, template: String) -> Result<(), JsError> {
+    // a new key is only registered when the route is inserted
+    let route_index = match self.route_indices.get(&route_key) {
+      Some(route_index) => *route_index,
+      None => self.route_keys.len(),
+    };
+
+    self
+      .router
+      .try_insert_route(route_index, template)
+      .map_err(|error| JsError::new(&error.to_string()))?;
+
+    if route_index == self.route_keys.len() {
+      self.route_keys.push(route_key.clone());
+      self.route_indices.insert(route_key, route_index);
+    }
+
+    Ok(())
+  }
+
+  /// Parse a path, returns the route key, or null, and an object with the parameters.
+  #[wasm_bindgen(js_name = parseRoute)]
+  pub fn parse_route(&self, path: &str) -> Array {
+    let (route_index, route_parameters) = self.router.router().parse_route(path);
+
+    let route_key = route_index
+      .map(|route_index| JsValue::from_str(&self.route_keys[route_index]))
+      .unwrap_or(JsValue::NULL);
+    let parameters = Object::new();
+    for (parameter_name, parameter_value) in route_parameters {
+      // setting a property on a plain object does not throw
+      let _ = Reflect::set(
+        &parameters,
+        &JsValue::from_str(parameter_name),
+        &JsValue::from_str(&parameter_value),
+      );
+    }
+
+    Array::of2(&route_key, &parameters)
+  }
+
+  /// The path of a route with the parameters filled in. Returns undefined if there is no route
+  /// with the key or if a parameter is missing, throws if a parameter value is not a string.
+  #[wasm_bindgen(js_name = stringifyRoute)]
+  pub fn stringify_route(
+    &self,
+    route_key: &str,
+    route_parameters: Option<Object>,
+  ) -> Result<Option<String>, JsError> {
+    let Some(route_index) = self.route_indices.get(route_key).copied() else {
+      return Ok(None);
+    };
+
+    let mut parameter_values = HashMap::new();
+    if let Some(route_parameters) = route_parameters {
+      for entry in Object::entries(&route_parameters).iter() {
+        let entry = Array::from(&entry);
+        let parameter_name = entry.get(0).as_string().unwrap_or_default();
+        let parameter_value = entry.get(1).as_string().ok_or_else(|| {
+          JsError::new(&format!(
+            "value for parameter {} is not a string",
+            parameter_name
+          ))
+        })?;
+        parameter_values.insert(parameter_name, parameter_value);
+      }
+    }
+    let route_parameters = parameter_values
+      .iter()
+      .map(|(name, value)| (name.as_str(), value.as_str()))
+      .collect();
+
+    Ok(
+      self
+        .router
+        .router()
+        .stringify_route(route_index, &route_parameters)
+        .map(|path| path.into_owned()),
+    )
+  }
+
+  /// Save the routes as json, in the order they were inserted.
+  #[wasm_bindgen(js_name = saveToJson)]
+  pub fn save_to_json(&self) -> Result<String, JsError> {
+    let router_json = RouterJson {
+      routes: self
+        .router
+        .router()
+        .routes()
+        .map(|(route_index, template, _parameter_names)| RouteJson {
+          key: self.route_keys[route_index].clone(),
+          template: template.to_owned(),
+        })
+        .collect(),
+    };
+
+    serde_json::to_string(&router_json).map_err(|error| JsError::new(&error.to_string()))
+  }
+
+  /// Create a router with the routes from `saveToJson`.
+  #[wasm_bindgen(js_name = loadFromJson)]
+  pub fn load_from_json(json: &str) -> Result<WasmRouter, JsError> {
+    let router_json: RouterJson =
+      serde_json::from_str(json).map_err(|error| JsError::new(&error.to_string()))?;
+
+    let mut router = Self::new();
+    for route_json in router_json.routes {
+      router.insert_route(route_json.key, route_json.template)?;
+    }
+
+    Ok(router)
+  }
+}
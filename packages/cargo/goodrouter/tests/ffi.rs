@@ -0,0 +1,208 @@
+#![cfg(feature = "ffi")]
+// c string literals need a newer compiler than the one in CI
+#![allow(clippy::manual_c_str_literals)]
+
+use goodrouter::{ffi::*, read_template_lines};
+use std::{
+  ffi::{CStr, CString},
+  fs,
+  path::PathBuf,
+  ptr, slice,
+};
+
+fn parameter(name: &'static str, value: &'static str) -> GoodrouterParameter {
+  GoodrouterParameter {
+    name: name.as_ptr().cast(),
+    name_length: name.len(),
+    value: value.as_ptr().cast(),
+    value_length: value.len(),
+  }
+}
+
+#[test]
+fn ffi_header() {
+  let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+  let out_dir = PathBuf::from(env!("OUT_DIR"));
+
+  // the header is generated by the build script, copy it to the include directory when the ffi
+  // changes
+  assert_eq!(
+    fs::read_to_string(crate_dir.join("include").join("goodrouter.h")).unwrap(),
+    fs::read_to_string(out_dir.join("goodrouter.h")).unwrap(),
+  );
+}
+
+#[test]
+fn ffi_router() {
+  unsafe {
+    let router = goodrouter_router_new();
+
+    assert_eq!(
+      goodrouter_router_insert_route(router, 1, b"/product/{id}\0".as_ptr().cast()),
+      GoodrouterStatus::Ok
+    );
+    assert_eq!(
+      goodrouter_router_insert_route(router, 2, b"/product/{x}\0".as_ptr().cast()),
+      GoodrouterStatus::AmbiguousRoute
+    );
+    assert_eq!(
+      goodrouter_router_insert_route(router, 3, b"/caf\xc3\xa9\0".as_ptr().cast()),
+      GoodrouterStatus::Ok
+    );
+    assert_eq!(
+      goodrouter_router_insert_route(router, 5, b"/\xff\0".as_ptr().cast()),
+      GoodrouterStatus::InvalidArgument
+    );
+    assert_eq!(
+      goodrouter_router_insert_route(router, 6, ptr::null()),
+      GoodrouterStatus::InvalidArgument
+    );
+    assert_eq!(
+      goodrouter_router_insert_route(ptr::null_mut(), 7, b"/\0".as_ptr().cast()),
+      GoodrouterStatus::InvalidArgument
+    );
+
+    // a null in a value is fine, the value has a length
+    let mut route_match = ptr::null_mut();
+    assert_eq!(
+      goodrouter_router_parse_route(
+        router,
+        b"/product/a%00b\0".as_ptr().cast(),
+        &mut route_match
+      ),
+      GoodrouterStatus::Ok
+    );
+    let parameters =
+      slice::from_raw_parts((*route_match).parameters, (*route_match).parameter_count);
+    assert_eq!((*route_match).route_key, 1);
+    assert_eq!(parameters.len(), 1);
+    assert_eq!(CStr::from_ptr(parameters[0].name).to_str(), Ok("id"));
+    assert_eq!(
+      slice::from_raw_parts(parameters[0].value.cast::<u8>(), parameters[0].value_length),
+      b"a\0b"
+    );
+
+    let path = goodrouter_router_stringify_route(router, 1, parameters.as_ptr(), parameters.len());
+    assert_eq!(CStr::from_ptr(path).to_str(), Ok("/product/a%00b"));
+    goodrouter_string_free(path);
+    goodrouter_match_free(route_match);
+
+    let mut route_match = ptr::null_mut();
+    assert_eq!(
+      goodrouter_router_parse_route(router, b"/not-found\0".as_ptr().cast(), &mut route_match),
+      GoodrouterStatus::NotFound
+    );
+    assert!(route_match.is_null());
+    assert_eq!(
+      goodrouter_router_parse_route(router, b"/product/%FF\0".as_ptr().cast(), &mut route_match),
+      GoodrouterStatus::ParameterValueDecode
+    );
+    assert!(route_match.is_null());
+    assert_eq!(
+      goodrouter_router_parse_route(router, ptr::null(), &mut route_match),
+      GoodrouterStatus::InvalidArgument
+    );
+    assert_eq!(
+      goodrouter_router_parse_route(ptr::null(), b"/\0".as_ptr().cast(), &mut route_match),
+      GoodrouterStatus::InvalidArgument
+    );
+    assert_eq!(
+      goodrouter_router_parse_route(router, b"/\0".as_ptr().cast(), ptr::null_mut()),
+      GoodrouterStatus::InvalidArgument
+    );
+
+    assert_eq!(
+      goodrouter_router_set_maximum_parameter_value_length(router, 3),
+      GoodrouterStatus::Ok
+    );
+    assert_eq!(
+      goodrouter_router_parse_route(router, b"/product/abcd\0".as_ptr().cast(), &mut route_match),
+      GoodrouterStatus::ParameterValueTooLong
+    );
+    assert!(route_match.is_null());
+    assert_eq!(
+      goodrouter_router_set_maximum_parameter_value_length(router, 0),
+      GoodrouterStatus::Ok
+    );
+    assert_eq!(
+      goodrouter_router_parse_route(router, b"/product/abcd\0".as_ptr().cast(), &mut route_match),
+      GoodrouterStatus::Ok
+    );
+    goodrouter_match_free(route_match);
+
+    assert_eq!(
+      goodrouter_router_set_maximum_parse_steps(router, 1),
+      GoodrouterStatus::Ok
+    );
+    assert_eq!(
+      goodrouter_router_parse_route(router, b"/product/1\0".as_ptr().cast(), &mut route_match),
+      GoodrouterStatus::TooManySteps
+    );
+    assert!(route_match.is_null());
+    assert_eq!(
+      goodrouter_router_set_maximum_parse_steps(router, 0),
+      GoodrouterStatus::Ok
+    );
+    assert_eq!(
+      goodrouter_router_set_maximum_parse_steps(ptr::null_mut(), 1),
+      GoodrouterStatus::InvalidArgument
+    );
+
+    let parameters = [parameter("x", "1")];
+    assert!(goodrouter_router_stringify_route(router, 1, parameters.as_ptr(), 1).is_null());
+    assert!(goodrouter_router_stringify_route(router, 8, ptr::null(), 0).is_null());
+    assert!(goodrouter_router_stringify_route(router, 1, ptr::null(), 1).is_null());
+
+    let path = goodrouter_router_stringify_route(router, 3, ptr::null(), 0);
+    assert_eq!(CStr::from_ptr(path).to_str(), Ok("/caf\u{e9}"));
+    goodrouter_string_free(path);
+
+    goodrouter_router_free(router);
+    goodrouter_router_free(ptr::null_mut());
+    goodrouter_match_free(ptr::null_mut());
+    goodrouter_string_free(ptr::null_mut());
+  }
+}
+
+#[test]
+fn ffi_router_github() {
+  let templates = read_template_lines(include_str!("../../../../fixtures/github.txt"));
+  let templates: Vec<_> = templates
+    .into_iter()
+    .map(|template| CString::new(template).unwrap())
+    .collect();
+
+  unsafe {
+    let router = goodrouter_router_new();
+    for (route_key, template) in templates.iter().enumerate() {
+      assert_eq!(
+        goodrouter_router_insert_route(router, route_key as u64, template.as_ptr()),
+        GoodrouterStatus::Ok
+      );
+    }
+
+    for template in &templates {
+      let path = template.to_str().unwrap().replace(['{', '}'], "");
+      let path = CString::new(path).unwrap();
+
+      let mut route_match = ptr::null_mut();
+      assert_eq!(
+        goodrouter_router_parse_route(router, path.as_ptr(), &mut route_match),
+        GoodrouterStatus::Ok
+      );
+
+      let stringified_path = goodrouter_router_stringify_route(
+        router,
+        (*route_match).route_key,
+        (*route_match).parameters,
+        (*route_match).parameter_count,
+      );
+      assert_eq!(CStr::from_ptr(stringified_path), path.as_c_str());
+
+      goodrouter_string_free(stringified_path);
+      goodrouter_match_free(route_match);
+    }
+
+    goodrouter_router_free(router);
+  }
+}
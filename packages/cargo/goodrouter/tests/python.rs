@@ -0,0 +1,102 @@
+#![cfg(feature = "python")]
+
+use goodrouter::python::PyRouter;
+use pyo3::{prelude::*, py_run};
+use std::path::PathBuf;
+
+fn fixture_path(file_name: &str) -> String {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("../../../fixtures")
+    .join(file_name)
+    .to_string_lossy()
+    .into_owned()
+}
+
+#[test]
+fn python_router() {
+  Python::initialize();
+  Python::attach(|py| {
+    let router_type = py.get_type::<PyRouter>();
+    py_run!(
+      py,
+      router_type,
+      r#"
+Router = router_type
+
+router = Router()
+router.insert_route("all-products", "/product/all")
+router.insert_route(("product", "detail"), "/product/{id}")
+router.insert_route(3, "/product/{id}/{tab}")
+
+assert router.parse_route("/product/all") == ("all-products", {})
+assert router.parse_route("/product/a%20b") == (("product", "detail"), {"id": "a b"})
+assert router.parse_route("/product/1/info") == (3, {"id": "1", "tab": "info"})
+assert router.parse_route("/not-found") == (None, {})
+
+assert router.stringify_route("all-products") == "/product/all"
+assert router.stringify_route(("product", "detail"), {"id": "a b"}) == "/product/a%20b"
+assert router.stringify_route(3, {"id": "1"}) is None
+assert router.stringify_route("unknown", {}) is None
+
+try:
+    router.insert_route("ambiguous", "/product/{x}")
+    assert False
+except ValueError as error:
+    assert str(error) == "ambiguous route"
+
+# the key of the route that is not inserted is not registered
+assert router.stringify_route("ambiguous", {"x": "1"}) is None
+
+try:
+    router.insert_route([], "/list")
+    assert False
+except TypeError:
+    pass
+"#
+    );
+  });
+}
+
+#[test]
+fn python_router_github() {
+  Python::initialize();
+  Python::attach(|py| {
+    let router_type = py.get_type::<PyRouter>();
+    let fixture_path = fixture_path("github.txt");
+    py_run!(
+      py,
+      router_type fixture_path,
+      r#"
+router = router_type.load_templates(fixture_path)
+
+with open(fixture_path) as file:
+    templates = [line.strip() for line in file if line.strip()]
+
+for template in templates:
+    path = template.replace("{", "").replace("}", "")
+    route_key, route_parameters = router.parse_route(path)
+    assert route_key in templates
+    assert router.stringify_route(route_key, route_parameters) == path
+"#
+    );
+  });
+}
+
+#[test]
+fn python_router_load_templates_missing() {
+  Python::initialize();
+  Python::attach(|py| {
+    let router_type = py.get_type::<PyRouter>();
+    py_run!(
+      py,
+      router_type,
+      r#"
+try:
+    router_type.load_templates("not-found.txt")
+    assert False
+except FileNotFoundError:
+    pass
+"#
+    );
+  });
+}
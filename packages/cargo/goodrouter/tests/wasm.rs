@@ -0,0 +1,140 @@
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+// run with `cargo test --target wasm32-unknown-unknown --features wasm --test wasm`, the tests run
+// in node via wasm-bindgen-test-runner
+
+use goodrouter::{read_template_lines, wasm::WasmRouter};
+use js_sys::{Object, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+fn parameters(entries: &[(&str, &str)]) -> Object {
+  let parameters = Object::new();
+  for (name, value) in entries {
+    Reflect::set(&parameters, &(*name).into(), &(*value).into()).unwrap();
+  }
+  parameters
+}
+
+fn parameter(parameters: &JsValue, name: &str) -> Option<String> {
+  Reflect::get(parameters, &name.into()).unwrap().as_string()
+}
+
+#[wasm_bindgen_test]
+fn wasm_router() {
+  let mut router = WasmRouter::new();
+  router
+    .insert_route("all-products".to_owned(), "/product/all".to_owned())
+    .map_err(JsValue::from)
+    .unwrap();
+  router
+    .insert_route("product-detail".to_owned(), "/product/{id}".to_owned())
+    .map_err(JsValue::from)
+    .unwrap();
+
+  let route = router.parse_route("/product/1");
+  assert_eq!(route.get(0).as_string().as_deref(), Some("product-detail"));
+  assert_eq!(parameter(&route.get(1), "id").as_deref(), Some("1"));
+
+  let route = router.parse_route("/product/all");
+  assert_eq!(route.get(0).as_string().as_deref(), Some("all-products"));
+  assert_eq!(Object::keys(&Object::from(route.get(1))).length(), 0);
+
+  let route = router.parse_route("/not-found");
+  assert!(route.get(0).is_null());
+
+  let path = router
+    .stringify_route("product-detail", Some(parameters(&[("id", "a b")])))
+    .map_err(JsValue::from)
+    .unwrap();
+  assert_eq!(path.as_deref(), Some("/product/a%20b"));
+
+  let path = router
+    .stringify_route("all-products", None)
+    .map_err(JsValue::from)
+    .unwrap();
+  assert_eq!(path.as_deref(), Some("/product/all"));
+
+  let path = router
+    .stringify_route("product-detail", None)
+    .map_err(JsValue::from)
+    .unwrap();
+  assert_eq!(path, None);
+
+  let path = router
+    .stringify_route("unknown", Some(parameters(&[])))
+    .map_err(JsValue::from)
+    .unwrap();
+  assert_eq!(path, None);
+}
+
+#[wasm_bindgen_test]
+fn wasm_router_errors() {
+  let mut router = WasmRouter::new();
+  router
+    .insert_route("a".to_owned(), "/{x}".to_owned())
+    .map_err(JsValue::from)
+    .unwrap();
+  assert!(router
+    .insert_route("b".to_owned(), "/{y}".to_owned())
+    .is_err());
+  // the key of the route that is not inserted is not registered
+  assert_eq!(
+    router
+      .stringify_route("b", Some(parameters(&[("y", "1")])))
+      .map_err(JsValue::from)
+      .unwrap(),
+    None
+  );
+
+  let route_parameters = Object::new();
+  Reflect::set(&route_parameters, &"x".into(), &JsValue::from_f64(1.0)).unwrap();
+  assert!(router.stringify_route("a", Some(route_parameters)).is_err());
+
+  assert!(WasmRouter::load_from_json("{}").is_err());
+}
+
+#[wasm_bindgen_test]
+fn wasm_router_json() {
+  let templates = read_template_lines(include_str!("../../../../fixtures/github.txt"));
+
+  let mut router = WasmRouter::new();
+  for template in &templates {
+    router
+      .insert_route(template.to_string(), template.to_string())
+      .map_err(JsValue::from)
+      .unwrap();
+  }
+
+  let json = router.save_to_json().map_err(JsValue::from).unwrap();
+  let loaded_router = WasmRouter::load_from_json(&json)
+    .map_err(JsValue::from)
+    .unwrap();
+  assert_eq!(
+    loaded_router.save_to_json().map_err(JsValue::from).unwrap(),
+    json
+  );
+
+  for template in &templates {
+    let route_parameters = Object::new();
+    let path = template.replace(['{', '}'], "");
+
+    let route = router.parse_route(&path);
+    let loaded_route = loaded_router.parse_route(&path);
+    assert_eq!(route.get(0), loaded_route.get(0));
+
+    let route_key = route.get(0).as_string().unwrap();
+    for name in Object::keys(&Object::from(route.get(1))).iter() {
+      let name = name.as_string().unwrap();
+      let value = parameter(&route.get(1), &name).unwrap();
+      assert_eq!(parameter(&loaded_route.get(1), &name), Some(value.clone()));
+      Reflect::set(&route_parameters, &name.into(), &value.into()).unwrap();
+    }
+
+    let stringified_path = loaded_router
+      .stringify_route(&route_key, Some(route_parameters))
+      .map_err(JsValue::from)
+      .unwrap();
+    assert_eq!(stringified_path, Some(path));
+  }
+}